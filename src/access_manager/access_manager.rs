@@ -1,12 +1,23 @@
 use scrypto::prelude::*;
 use crate::access_manager::custom_types::*;
+use crate::access_manager::errors::{ensure, AccessManagerError};
 
 #[blueprint]
+#[events(
+    crate::access_manager::events::PermissionsRenouncedEvent,
+    crate::access_manager::events::KeyRenouncedEvent,
+    crate::access_manager::events::KeyBadgesBurnedEvent,
+    crate::access_manager::events::KeyBadgesRecalledAndBurnedEvent,
+    crate::access_manager::events::ValidatorOperationProposedEvent,
+    crate::access_manager::events::ValidatorProposalExecutedEvent,
+    crate::access_manager::events::ValidatorProposalVetoedEvent,
+)]
 mod access_manager {
 
     enable_function_auth! {
         new => rule!(allow_all);
         new_with_address_reservation => rule!(allow_all);
+        new_with_auth_badge => rule!(allow_all);
     }
     enable_method_auth! { 
         roles { 
@@ -20,52 +31,166 @@ mod access_manager {
             // `create_access_key_badge` was intentionally removed in v2; use `create_basic_key_badge` instead.
             create_basic_key_badge => PUBLIC;
             create_custom_access_key_badge =>  PUBLIC;
+            create_custom_access_key_badge_with_policy => PUBLIC;
+            create_bound_access_key_badge => PUBLIC;
+            transfer_key_badge => PUBLIC;
+            create_session_key => PUBLIC;
+            renounce_permissions => PUBLIC;
+            renounce_key => PUBLIC;
             recall_key_badge =>  PUBLIC;
+            recall_key_badges_by_id => PUBLIC;
+            recall_and_burn_key_badges => PUBLIC;
             burn_key_badge => restrict_to: [component_owner, key_holder];
             create_auth_badge_proof => PUBLIC;
             withdraw_auth_badge => restrict_to: [component_owner];
+            set_payout_account => restrict_to: [component_owner];
+            register_signature_delegate => restrict_to: [component_owner];
+            remove_signature_delegate => restrict_to: [component_owner];
+            set_rate_limit => restrict_to: [component_owner];
+            set_key_rate_limit => restrict_to: [component_owner];
+            set_delegate_rate_limit => restrict_to: [component_owner];
+            set_validator_operation_delay => restrict_to: [component_owner];
+            register_guardian => restrict_to: [component_owner];
+            remove_guardian => restrict_to: [component_owner];
+            deposit_to_treasury => PUBLIC;
+            withdraw_from_treasury => PUBLIC;
+
+            // view methods
+            get_auth_badge_info => PUBLIC;
+            get_resource_addresses => PUBLIC;
+            get_validator_address => PUBLIC;
+            get_config => PUBLIC;
+            get_signature_delegates => PUBLIC;
+            get_rate_limits => PUBLIC;
+            get_key_rate_limit => PUBLIC;
+            get_delegate_rate_limit => PUBLIC;
+            get_validator_operation_delays => PUBLIC;
+            get_guardians => PUBLIC;
+            get_validator_proposals => PUBLIC;
+            get_key_burned_at => PUBLIC;
+            describe_key_permissions => PUBLIC;
+            can_call => PUBLIC;
 
             // validator extension methods
             validator_register => PUBLIC;
             validator_unregister => PUBLIC;
             validator_stake_as_owner => PUBLIC;
+            validator_stake_as_owner_to_payout => PUBLIC;
+            validator_stake_from_treasury => PUBLIC;
             validator_update_key => PUBLIC;
+            propose_validator_operation => PUBLIC;
+            execute_validator_proposal => PUBLIC;
+            veto_validator_proposal => PUBLIC;
             validator_update_fee => PUBLIC;
             validator_update_accept_delegated_stake => PUBLIC;
             validator_signal_protocol_update_readiness => PUBLIC;
             validator_lock_owner_stake_units => PUBLIC;
+            validator_lock_owner_stake_units_from_treasury => PUBLIC;
             validator_start_unlock_owner_stake_units => PUBLIC;
             validator_finish_unlock_owner_stake_units => PUBLIC;
+            validator_finish_unlock_owner_stake_units_to_payout => PUBLIC;
+            validator_compound_owner_stake => PUBLIC;
+            validator_set_metadata => PUBLIC;
+            validator_lock_metadata => PUBLIC;
+            validator_set_metadata_role => PUBLIC;
         }
     }
     enable_package_royalties! {
         new => Usd(dec!(0.01));
         new_with_address_reservation => Usd(dec!(0.01));
+        new_with_auth_badge => Usd(dec!(0.01));
         deposit_auth_badge => Usd(dec!(0.01));
         create_super_access_key_badge => Usd(dec!(0.01));
         create_basic_key_badge => Usd(dec!(0.01));
         create_custom_access_key_badge => Usd(dec!(0.01));
+        create_custom_access_key_badge_with_policy => Usd(dec!(0.01));
+        create_bound_access_key_badge => Usd(dec!(0.01));
+        transfer_key_badge => Usd(dec!(0.01));
+        create_session_key => Usd(dec!(0.01));
+        renounce_permissions => Usd(dec!(0.01));
+        renounce_key => Usd(dec!(0.01));
         recall_key_badge => Usd(dec!(0.01));
+        recall_key_badges_by_id => Usd(dec!(0.01));
+        recall_and_burn_key_badges => Usd(dec!(0.01));
         burn_key_badge => Usd(dec!(0.01));
         create_auth_badge_proof => Usd(dec!(0.01));
         withdraw_auth_badge => Usd(dec!(0.01));
+        set_payout_account => Usd(dec!(0.01));
+        register_signature_delegate => Usd(dec!(0.01));
+        remove_signature_delegate => Usd(dec!(0.01));
+        set_rate_limit => Usd(dec!(0.01));
+        set_key_rate_limit => Usd(dec!(0.01));
+        set_delegate_rate_limit => Usd(dec!(0.01));
+        set_validator_operation_delay => Usd(dec!(0.01));
+        register_guardian => Usd(dec!(0.01));
+        remove_guardian => Usd(dec!(0.01));
+        deposit_to_treasury => Usd(dec!(0.01));
+        withdraw_from_treasury => Usd(dec!(0.01));
+
+        // view methods are free so frontends can query them through preview
+        get_auth_badge_info => Free;
+        get_resource_addresses => Free;
+        get_validator_address => Free;
+        get_config => Free;
+        get_signature_delegates => Free;
+        get_rate_limits => Free;
+        get_key_rate_limit => Free;
+        get_delegate_rate_limit => Free;
+        get_validator_operation_delays => Free;
+        get_guardians => Free;
+        get_validator_proposals => Free;
+        get_key_burned_at => Free;
+        describe_key_permissions => Free;
+        can_call => Free;
 
         // validator extension methods
         validator_register => Usd(dec!(0.01));
         validator_unregister => Usd(dec!(0.01));
         validator_stake_as_owner => Usd(dec!(0.01));
+        validator_stake_as_owner_to_payout => Usd(dec!(0.01));
+        validator_stake_from_treasury => Usd(dec!(0.01));
         validator_update_key => Usd(dec!(0.01));
+        propose_validator_operation => Usd(dec!(0.01));
+        execute_validator_proposal => Usd(dec!(0.01));
+        veto_validator_proposal => Usd(dec!(0.01));
         validator_update_fee => Usd(dec!(0.01));
         validator_update_accept_delegated_stake => Usd(dec!(0.01));
         validator_signal_protocol_update_readiness => Usd(dec!(0.01));
         validator_lock_owner_stake_units => Usd(dec!(0.01));
+        validator_lock_owner_stake_units_from_treasury => Usd(dec!(0.01));
         validator_start_unlock_owner_stake_units => Usd(dec!(0.01));
         validator_finish_unlock_owner_stake_units => Usd(dec!(0.01));
+        validator_finish_unlock_owner_stake_units_to_payout => Usd(dec!(0.01));
+        validator_compound_owner_stake => Usd(dec!(0.01));
+        validator_set_metadata => Usd(dec!(0.01));
+        validator_lock_metadata => Usd(dec!(0.01));
+        validator_set_metadata_role => Usd(dec!(0.01));
     }
     pub struct AccessManager {
         pub auth_badge: NonFungibleVault,
         pub owner_badge_resource_manager: ResourceManager,
         pub access_key_badge_resource_manager: ResourceManager,
+        // transaction-scoped keys, they can neither be deposited nor burned outside of `burn_key_badge`
+        pub session_key_badge_resource_manager: ResourceManager,
+        // where the `_to_payout` validator methods send their outputs, configured by the owner
+        pub payout_account: Option<ComponentAddress>,
+        // XRD and owner stake units held by the component so delegates can stake and lock without supplying buckets
+        pub xrd_treasury: FungibleVault,
+        pub stake_unit_treasury: Option<FungibleVault>,
+        // virtual signature badges registered by the owner, accepted from the auth zone when no proof is passed
+        pub signature_delegates: IndexMap<NonFungibleGlobalId, Vec<KeyBadgePermission>>,
+        // rate limits per permission set by the owner, overridable per key or signature delegate, and the uses counted against them
+        pub rate_limits: IndexMap<KeyBadgePermission, RateLimit>,
+        pub rate_limit_overrides: KeyValueStore<(RateLimitedCaller, KeyBadgePermission), RateLimit>,
+        pub rate_limit_usage: KeyValueStore<(RateLimitedCaller, KeyBadgePermission), RateLimitUsage>,
+        // delays in seconds of the delayable validator methods, delegates must propose calls to them
+        pub validator_operation_delays: IndexMap<String, i64>,
+        // badges that may veto validator proposals from the auth zone, besides the owner
+        pub guardians: IndexSet<NonFungibleGlobalId>,
+        pub validator_proposals: IndexMap<u64, ValidatorProposal>,
+        pub next_proposal_id: u64,
+        // when each burned access key was burned, session keys are not recorded
+        pub burned_keys: KeyValueStore<NonFungibleLocalId, Instant>,
     }
     impl AccessManager {
         pub fn new(auth_badge_address: ResourceAddress, dapp_definition_address: ComponentAddress) -> (Global<AccessManager>,  NonFungibleBucket) {
@@ -73,11 +198,24 @@ mod access_manager {
             Self::new_with_address_reservation(auth_badge_address, dapp_definition_address, address_reservation)
         }
         pub fn new_with_address_reservation(auth_badge_address: ResourceAddress, dapp_definition_address: ComponentAddress, address_reservation: GlobalAddressReservation) -> (Global<AccessManager>,  NonFungibleBucket) {
+            Self::instantiate_manager(auth_badge_address, None, dapp_definition_address, address_reservation)
+        }
+        ///
+        /// Instantiates the manager with the auth badge already deposited, proving the creator controls it.
+        /// The owner badge and the component carry `auth_badge_custody_verified = true` metadata, managers created with `new` carry `false`.
+        ///
+        pub fn new_with_auth_badge(auth_badge: NonFungibleBucket, dapp_definition_address: ComponentAddress) -> (Global<AccessManager>,  NonFungibleBucket) {
+            ensure(auth_badge.amount() == Decimal::ONE, AccessManagerError::InvalidAuthBadgeAmount);
+            let (address_reservation, _component_address) = Runtime::allocate_component_address(AccessManager::blueprint_id());
+            Self::instantiate_manager(auth_badge.resource_address(), Some(auth_badge), dapp_definition_address, address_reservation)
+        }
+        fn instantiate_manager(auth_badge_address: ResourceAddress, auth_badge: Option<NonFungibleBucket>, dapp_definition_address: ComponentAddress, address_reservation: GlobalAddressReservation) -> (Global<AccessManager>,  NonFungibleBucket) {
+            let custody_verified = auth_badge.is_some();
             let global_address = Runtime::get_reservation_address(&address_reservation);
             let component_address = ComponentAddress::try_from_hex(global_address.to_hex().as_str()).unwrap();
             let owner_badge_data = OwnerBadgeData {
                 manager_component_address: component_address,
-                auth_badge_address: auth_badge_address
+                auth_badge_address
             };
             let owner_badge = ResourceBuilder::new_ruid_non_fungible::<OwnerBadgeData>(OwnerRole::None)
                 .metadata(metadata! (
@@ -93,6 +231,7 @@ mod access_manager {
                         "description" => "Access Manager Owner badge belongs to the owner of the auth badge which this access manager component is managing", locked;
                         "tags" => vec!["Badge", "Access Control", "Owner Badge"], locked;
                         "dapp_definitions" => vec![dapp_definition_address], locked;
+                        "auth_badge_custody_verified" => custody_verified, locked;
                     }
                 ))
                 .mint_roles(mint_roles! (
@@ -156,7 +295,8 @@ mod access_manager {
                     withdrawer_updater => rule!(deny_all);
                 ))
                 .deposit_roles(deposit_roles! (
-                    depositor => rule!(require(owner_badge.resource_address()) || require(key_badge_address));
+                    // the component deposits keys moved by `transfer_key_badge`
+                    depositor => rule!(require(owner_badge.resource_address()) || require(key_badge_address) || require(global_caller(component_address)));
                     depositor_updater => rule!(deny_all);
                 ))
                 .recall_roles(recall_roles! (
@@ -173,10 +313,73 @@ mod access_manager {
                 ))
                 .create_with_no_initial_supply();
 
+            // session keys cannot be deposited anywhere, so the transaction only commits once they are burned through `burn_key_badge`
+            let session_key_badge_resource_manager = ResourceBuilder::new_ruid_non_fungible::<AccessKeyBadgeData>(OwnerRole::None)
+                .metadata(metadata! (
+                    roles {
+                        metadata_locker => OWNER;
+                        metadata_locker_updater => OWNER;
+                        metadata_setter => OWNER;
+                        metadata_setter_updater => OWNER;
+                    },
+                    init {
+                        "manager_component_address" => component_address, locked;
+                        "name" => "Access Manager Session Key Badge", locked;
+                        "description" => "Access Manager Session Key badge carries a subset of a key's permissions for a single transaction and must be burned through burn_key_badge before it ends", locked;
+                        "tags" => vec!["Badge", "Access Control", "Session Key Badge"], locked;
+                        "dapp_definitions" => vec![dapp_definition_address], locked;
+                    }
+                ))
+                .mint_roles(mint_roles! (
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                ))
+                .burn_roles(burn_roles! (
+                    burner => rule!(require(global_caller(component_address)));
+                    burner_updater => rule!(deny_all);
+                ))
+                .withdraw_roles(withdraw_roles! (
+                    withdrawer => rule!(deny_all);
+                    withdrawer_updater => rule!(deny_all);
+                ))
+                .deposit_roles(deposit_roles! (
+                    depositor => rule!(deny_all);
+                    depositor_updater => rule!(deny_all);
+                ))
+                .recall_roles(recall_roles! (
+                    recaller => rule!(deny_all);
+                    recaller_updater => rule!(deny_all);
+                ))
+                .freeze_roles(freeze_roles! (
+                    freezer => rule!(deny_all);
+                    freezer_updater => rule!(deny_all);
+                ))
+                .non_fungible_data_update_roles(non_fungible_data_update_roles! (
+                    non_fungible_data_updater => rule!(deny_all);
+                    non_fungible_data_updater_updater => rule!(deny_all);
+                ))
+                .create_with_no_initial_supply();
+
             let component = Self {
-                                auth_badge: NonFungibleVault::new(auth_badge_address),
+                                auth_badge: match auth_badge {
+                                    Some(auth_badge) => NonFungibleVault::with_bucket(auth_badge),
+                                    None => NonFungibleVault::new(auth_badge_address)
+                                },
                                 owner_badge_resource_manager: owner_badge.resource_manager(),
-                                access_key_badge_resource_manager: access_key_badge_resource_manager
+                                access_key_badge_resource_manager,
+                                session_key_badge_resource_manager,
+                                payout_account: None,
+                                xrd_treasury: FungibleVault::new(XRD),
+                                stake_unit_treasury: None,
+                                signature_delegates: IndexMap::new(),
+                                rate_limits: IndexMap::new(),
+                                rate_limit_overrides: KeyValueStore::new(),
+                                rate_limit_usage: KeyValueStore::new(),
+                                validator_operation_delays: IndexMap::new(),
+                                guardians: IndexSet::new(),
+                                validator_proposals: IndexMap::new(),
+                                next_proposal_id: 0,
+                                burned_keys: KeyValueStore::new()
                             }
                 .instantiate()
                 .prepare_to_globalize(OwnerRole::Updatable(rule!(require(global_caller(component_address)))))
@@ -189,6 +392,7 @@ mod access_manager {
                     },
                     init {
                         "dapp_definition" => dapp_definition_address, locked;
+                        "auth_badge_custody_verified" => custody_verified, locked;
                     }
                 ))
                 .roles(roles! (
@@ -202,8 +406,8 @@ mod access_manager {
         }
         
         pub fn deposit_auth_badge(&mut self, auth_badge: NonFungibleBucket) {
-            assert!(self.auth_badge.is_empty(), "Access Manager component already has an auth badge!");
-            assert!(auth_badge.amount() == Decimal::ONE, "Cannot deposit any amount other than exactly one!");
+            ensure(self.auth_badge.is_empty(), AccessManagerError::AuthBadgeAlreadyPresent);
+            ensure(auth_badge.amount() == Decimal::ONE, AccessManagerError::InvalidAuthBadgeAmount);
             self.auth_badge.put(auth_badge);
         }
         pub fn create_super_access_key_badge(&mut self, include_validator_permissions: bool) -> NonFungibleBucket {
@@ -227,10 +431,10 @@ mod access_manager {
                     KeyBadgePermission::Validator_FinishUnlockOwnerStakeUnits
                 ]);
             }
-            self.internal_create_custom_access_key_badge(permissions)
+            self.internal_create_custom_access_key_badge(permissions, KeyTransferPolicy::Soulbound, None)
         }
     // `create_access_key_badge` was intentionally removed in v2; use `create_basic_key_badge` (owner or key-holder with proper proof) instead.
-        pub fn create_basic_key_badge(&mut self, include_validator_permissions: bool, proof: NonFungibleProof) -> NonFungibleBucket {
+        pub fn create_basic_key_badge(&mut self, include_validator_permissions: bool, proof: Option<NonFungibleProof>) -> NonFungibleBucket {
             // can be called by either the owner or a key holder
             // need to check if the key holder has the right permissions
             let mut permissions = vec![
@@ -253,102 +457,346 @@ mod access_manager {
 
             crate::access_manager::access_manager_helper::check_caller_permissions(self, KeyBadgePermission::CreateAccessKey, Some(permissions.clone()), proof);
 
-            self.internal_create_custom_access_key_badge(permissions)
+            self.internal_create_custom_access_key_badge(permissions, KeyTransferPolicy::Soulbound, None)
         }
-        pub fn create_custom_access_key_badge(&mut self, permissions: Vec<String>, proof: NonFungibleProof) -> NonFungibleBucket {
-            let permissions_enum: Result<Vec<_>, _> = permissions
-                .into_iter()
-                .map(|s| KeyBadgePermission::from_str(&s).map_err(|e| e.to_string()))
-                .collect();
-
-           let permissions_vec = match permissions_enum {
-                Ok(v) => v,
-                Err(e) => {
-                    panic!("Invalid permission string: {}", e); 
-                    // or handle differently
-                }
-            };
-            // if component_owner, accept super permissions as is
-            // if key holder, super permissions must be none
-            if proof.resource_manager().address() == self.access_key_badge_resource_manager.address() {
-                // if the proof is an access key badge, make sure no super permissions are included
-                if permissions_vec.iter().any(|p| matches!(p, KeyBadgePermission::CreateAccessKey | KeyBadgePermission::RecallAccessKey)) {
-                    panic!("Key holders cannot create access key badges with super permissions!");
-                }
-            }
-
-            // can be called by either the owner or a key holder
-            // need to check if the key holder has the right permissions
-            crate::access_manager::access_manager_helper::check_caller_permissions(self, KeyBadgePermission::CreateAccessKey, Some(permissions_vec.clone()), proof);
-            
-            self.internal_create_custom_access_key_badge(permissions_vec)
+        pub fn create_custom_access_key_badge(&mut self, permissions: Vec<String>, proof: Option<NonFungibleProof>) -> NonFungibleBucket {
+            self.create_custom_access_key_badge_with_policy(permissions, KeyTransferPolicy::Soulbound, proof)
+        }
+        pub fn create_custom_access_key_badge_with_policy(&mut self, permissions: Vec<String>, transfer_policy: KeyTransferPolicy, proof: Option<NonFungibleProof>) -> NonFungibleBucket {
+            self.create_custom_key(permissions, transfer_policy, None, proof)
         }
-        pub fn recall_key_badge(&mut self, vault_address: InternalAddress, proof: NonFungibleProof) -> NonFungibleBucket {
+        ///
+        /// Same as `create_custom_access_key_badge_with_policy`, but the key is only accepted when presented together with a proof of controlling `bound_account`.
+        ///
+        pub fn create_bound_access_key_badge(&mut self, permissions: Vec<String>, transfer_policy: KeyTransferPolicy, bound_account: ComponentAddress, proof: Option<NonFungibleProof>) -> NonFungibleBucket {
+            self.create_custom_key(permissions, transfer_policy, Some(bound_account), proof)
+        }
+        pub fn recall_key_badge(&mut self, vault_address: InternalAddress, proof: Option<NonFungibleProof>) -> NonFungibleBucket {
             crate::access_manager::access_manager_helper::check_caller_permissions(self, KeyBadgePermission::RecallAccessKey, None, proof);
             
             let recalled_bucket: Bucket = scrypto_decode(&ScryptoVmV1Api::object_call_direct(
                 vault_address.as_node_id(),
                 VAULT_RECALL_IDENT,
                 scrypto_args!(Decimal::ONE),
-              )).unwrap();
+              )).unwrap_or_else(|error| AccessManagerError::NotAKeyBadge.panic_with(format!("{:?}", error)));
           
-              recalled_bucket.as_non_fungible()
+              let recalled_bucket = recalled_bucket.as_non_fungible();
+              crate::validator_extension::validator_extension::drop_key_proposals(self, &recalled_bucket.non_fungible_local_ids());
+              recalled_bucket
+        }
+        ///
+        /// Recalls exactly the key badges `ids` from `vault_address`, unlike `recall_key_badge`, which recalls any one key of the vault.
+        /// Recalling a key, by any of the recall methods, drops its pending validator proposals.
+        ///
+        pub fn recall_key_badges_by_id(&mut self, vault_address: InternalAddress, ids: IndexSet<NonFungibleLocalId>, proof: Option<NonFungibleProof>) -> NonFungibleBucket {
+            crate::access_manager::access_manager_helper::check_caller_permissions(self, KeyBadgePermission::RecallAccessKey, None, proof);
+            crate::validator_extension::validator_extension::drop_key_proposals(self, &ids);
+            crate::access_manager::access_manager_helper::recall_key_badges(self, vault_address, ids)
+        }
+        ///
+        /// Recalls the listed keys of every vault and burns them without passing them through the caller's worktop.
+        ///
+        pub fn recall_and_burn_key_badges(&mut self, keys: Vec<(InternalAddress, Vec<NonFungibleLocalId>)>, proof: Option<NonFungibleProof>) {
+            crate::access_manager::access_manager_helper::check_caller_permissions(self, KeyBadgePermission::RecallAccessKey, None, proof);
+            let key_ids = keys.iter().flat_map(|(_, ids)| ids.iter().cloned()).collect();
+            crate::validator_extension::validator_extension::drop_key_proposals(self, &key_ids);
+            crate::access_manager::access_manager_helper::recall_and_burn_key_badges(self, keys);
+        }
+        pub fn transfer_key_badge(&mut self, key_vault: InternalAddress, key_id: NonFungibleLocalId, destination: ComponentAddress, proof: NonFungibleProof) {
+            crate::access_manager::access_manager_helper::transfer_key_badge(self, key_vault, key_id, destination, proof);
+        }
+        ///
+        /// Mints a session key holding `permissions`, each of which the key behind `proof` must hold (the owner may grant any).
+        /// The session key can be passed as a proof within the transaction, e.g. into third-party components, and must be burned
+        /// with `burn_key_badge` before the transaction ends, otherwise it fails.
+        ///
+        pub fn create_session_key(&mut self, permissions: Vec<String>, proof: NonFungibleProof) -> NonFungibleBucket {
+            let permissions = crate::access_manager::access_manager_helper::parse_permissions(permissions);
+            crate::access_manager::access_manager_helper::check_session_key_permissions(self, &permissions, proof);
+            let session_key_data = AccessKeyBadgeData {
+                manager_component_address: Runtime::global_address(),
+                auth_badge_address: self.auth_badge.resource_address(),
+                permissions,
+                transfer_policy: KeyTransferPolicy::Soulbound,
+                bound_account: None
+            };
+            self.session_key_badge_resource_manager.mint_ruid_non_fungible(session_key_data).as_non_fungible()
+        }
+        ///
+        /// Removes `permissions` from the key behind `proof`, permissions the key does not hold are ignored.
+        ///
+        pub fn renounce_permissions(&mut self, permissions: Vec<String>, proof: NonFungibleProof) {
+            let permissions = crate::access_manager::access_manager_helper::parse_permissions(permissions);
+            crate::access_manager::access_manager_helper::renounce_permissions(self, permissions, proof);
         }
+        ///
+        /// Burns the key behind `proof`. Holders cannot withdraw their key badge, so the component recalls it from `key_vault`.
+        ///
+        pub fn renounce_key(&mut self, key_vault: InternalAddress, proof: NonFungibleProof) {
+            crate::access_manager::access_manager_helper::renounce_key(self, key_vault, proof);
+        }
+        ///
+        /// Burns access keys or session keys of this manager, any other resource is rejected with `NotAKeyBadge`.
+        ///
         pub fn burn_key_badge(&mut self, key_badge: NonFungibleBucket) {
-            key_badge.burn();
+            crate::access_manager::access_manager_helper::burn_key_badge(self, key_badge);
         }
-        pub fn create_auth_badge_proof(&mut self, proof: NonFungibleProof) -> NonFungibleProof {
+        pub fn create_auth_badge_proof(&mut self, proof: Option<NonFungibleProof>) -> NonFungibleProof {
             crate::access_manager::access_manager_helper::check_caller_permissions(self, KeyBadgePermission::CreateNativeProof, None, proof);
-            
+            ensure(!self.auth_badge.is_empty(), AccessManagerError::EmptyAuthVault);
+
             self.auth_badge.as_non_fungible().create_proof_of_non_fungibles(&self.auth_badge.as_non_fungible().non_fungible_local_ids(1))
         }
         pub fn withdraw_auth_badge(&mut self) -> NonFungibleBucket {
-            assert!(self.auth_badge.amount() == Decimal::ONE, "Cannot create a proof with an empty vault, deposit the auth badge first!");
+            ensure(self.auth_badge.amount() == Decimal::ONE, AccessManagerError::EmptyAuthVault);
             self.auth_badge.take(1)
         }
-    
+        pub fn set_payout_account(&mut self, payout_account: Option<ComponentAddress>) {
+            self.payout_account = payout_account;
+        }
+        ///
+        /// Lets whoever signs with `delegate` (a Secp256k1 or Ed25519 signature badge) use `permissions` by passing `None` as the proof.
+        /// Registering an already registered delegate replaces its permissions.
+        ///
+        pub fn register_signature_delegate(&mut self, delegate: NonFungibleGlobalId, permissions: Vec<String>) {
+            ensure(crate::access_manager::access_manager_helper::is_signature_badge(&delegate), AccessManagerError::InvalidSignatureDelegate);
+            let permissions = crate::access_manager::access_manager_helper::parse_permissions(permissions);
+            self.signature_delegates.insert(delegate, permissions);
+        }
+        // also drops the delegate's rate limit overrides, registering it again starts from the component-wide limits, and its pending validator proposals
+        pub fn remove_signature_delegate(&mut self, delegate: NonFungibleGlobalId) {
+            ensure(self.signature_delegates.swap_remove(&delegate).is_some(), AccessManagerError::SignatureDelegateNotRegistered);
+            crate::validator_extension::validator_extension::drop_delegate_proposals(self, &delegate);
+            crate::access_manager::access_manager_helper::clear_rate_limit_overrides(self, &RateLimitedCaller::SignatureDelegate(delegate));
+        }
+        ///
+        /// Limits how often every key and signature delegate may use `permission`, `None` lifts the limit. Keys and delegates with an override keep it.
+        ///
+        pub fn set_rate_limit(&mut self, permission: String, rate_limit: Option<RateLimit>) {
+            let permission = crate::access_manager::access_manager_helper::parse_permissions(vec![permission]).remove(0);
+            match rate_limit {
+                Some(rate_limit) => {
+                    crate::access_manager::access_manager_helper::validate_rate_limit(&rate_limit);
+                    self.rate_limits.insert(permission, rate_limit);
+                }
+                None => {
+                    self.rate_limits.swap_remove(&permission);
+                }
+            }
+        }
+        ///
+        /// Delays calls of `method_name` (`validator_update_key` or `validator_unregister`) by `delay_seconds` of ledger time:
+        /// only the owner may still call it directly, delegates must use `propose_validator_operation`. `None` removes the delay.
+        /// The delay is at most `MAX_VALIDATOR_OPERATION_DELAY_SECONDS`, and also applies to the proposals already pending.
+        ///
+        pub fn set_validator_operation_delay(&mut self, method_name: String, delay_seconds: Option<i64>) {
+            ensure(DELAYABLE_VALIDATOR_METHODS.contains(&method_name.as_str()), AccessManagerError::InvalidProposalDelay);
+            match delay_seconds {
+                Some(delay_seconds) => {
+                    ensure(delay_seconds >= 0, AccessManagerError::InvalidProposalDelay);
+                    ensure(delay_seconds <= MAX_VALIDATOR_OPERATION_DELAY_SECONDS, AccessManagerError::ProposalDelayTooLong);
+                    self.validator_operation_delays.insert(method_name, delay_seconds);
+                }
+                None => {
+                    self.validator_operation_delays.swap_remove(&method_name);
+                }
+            }
+        }
+        // `guardian` may veto validator proposals by passing no proof, its badge must be in the auth zone
+        pub fn register_guardian(&mut self, guardian: NonFungibleGlobalId) {
+            self.guardians.insert(guardian);
+        }
+        pub fn remove_guardian(&mut self, guardian: NonFungibleGlobalId) {
+            ensure(self.guardians.swap_remove(&guardian), AccessManagerError::GuardianNotRegistered);
+        }
+        ///
+        /// Overrides the rate limit of `permission` for the key `key_id`, `None` falls back to the component-wide limit.
+        ///
+        pub fn set_key_rate_limit(&mut self, key_id: NonFungibleLocalId, permission: String, rate_limit: Option<RateLimit>) {
+            crate::access_manager::access_manager_helper::set_rate_limit_override(self, RateLimitedCaller::AccessKey(key_id), permission, rate_limit);
+        }
+        ///
+        /// Overrides the rate limit of `permission` for the registered signature delegate `delegate`, `None` falls back to the component-wide limit.
+        ///
+        pub fn set_delegate_rate_limit(&mut self, delegate: NonFungibleGlobalId, permission: String, rate_limit: Option<RateLimit>) {
+            ensure(self.signature_delegates.contains_key(&delegate), AccessManagerError::SignatureDelegateNotRegistered);
+            crate::access_manager::access_manager_helper::set_rate_limit_override(self, RateLimitedCaller::SignatureDelegate(delegate), permission, rate_limit);
+        }
+
+        // treasury methods
+        pub fn deposit_to_treasury(&mut self, bucket: Bucket, proof: Option<NonFungibleProof>) {
+            crate::validator_extension::validator_extension::deposit_to_treasury(self, bucket, proof);
+        }
+        pub fn withdraw_from_treasury(&mut self, resource_address: ResourceAddress, amount: Decimal, proof: Option<NonFungibleProof>) -> Bucket {
+            crate::validator_extension::validator_extension::withdraw_from_treasury(self, resource_address, amount, proof)
+        }
+
+        // view methods
+        pub fn get_auth_badge_info(&self) -> AuthBadgeInfo {
+            let deposited = !self.auth_badge.is_empty();
+            AuthBadgeInfo {
+                resource_address: self.auth_badge.resource_address(),
+                deposited,
+                local_id: if deposited { Some(self.auth_badge.non_fungible_local_id()) } else { None }
+            }
+        }
+        pub fn get_resource_addresses(&self) -> ResourceAddresses {
+            ResourceAddresses {
+                auth_badge: self.auth_badge.resource_address(),
+                owner_badge: self.owner_badge_resource_manager.address(),
+                access_key_badge: self.access_key_badge_resource_manager.address(),
+                session_key_badge: self.session_key_badge_resource_manager.address()
+            }
+        }
+        pub fn get_validator_address(&self) -> Option<ComponentAddress> {
+            crate::validator_extension::validator_extension::validator_address(self)
+        }
+        pub fn get_config(&self) -> AccessManagerConfig {
+            AccessManagerConfig {
+                auth_badge: self.get_auth_badge_info(),
+                resources: self.get_resource_addresses(),
+                validator: self.get_validator_address(),
+                payout_account: self.payout_account,
+                xrd_treasury_amount: self.xrd_treasury.amount(),
+                stake_unit_treasury: self.stake_unit_treasury.as_ref().map(|vault| (vault.resource_address(), vault.amount()))
+            }
+        }
+        pub fn get_signature_delegates(&self) -> IndexMap<NonFungibleGlobalId, Vec<KeyBadgePermission>> {
+            self.signature_delegates.clone()
+        }
+        pub fn get_validator_operation_delays(&self) -> IndexMap<String, i64> {
+            self.validator_operation_delays.clone()
+        }
+        pub fn get_guardians(&self) -> IndexSet<NonFungibleGlobalId> {
+            self.guardians.clone()
+        }
+        // the pending proposals by id, executed and vetoed proposals are removed
+        pub fn get_validator_proposals(&self) -> IndexMap<u64, ValidatorProposal> {
+            self.validator_proposals.clone()
+        }
+        // when the access key `key_id` was burned, `None` while it exists
+        pub fn get_key_burned_at(&self, key_id: NonFungibleLocalId) -> Option<Instant> {
+            self.burned_keys.get(&key_id).map(|burned_at| *burned_at)
+        }
+        pub fn get_rate_limits(&self) -> IndexMap<KeyBadgePermission, RateLimit> {
+            self.rate_limits.clone()
+        }
+        // the rate limit the key is held to for `permission`, its override or the component-wide limit
+        pub fn get_key_rate_limit(&self, key_id: NonFungibleLocalId, permission: String) -> Option<RateLimit> {
+            let permission = crate::access_manager::access_manager_helper::parse_permissions(vec![permission]).remove(0);
+            crate::access_manager::access_manager_helper::effective_rate_limit(self, &RateLimitedCaller::AccessKey(key_id), &permission)
+        }
+        // the rate limit the signature delegate is held to for `permission`, its override or the component-wide limit
+        pub fn get_delegate_rate_limit(&self, delegate: NonFungibleGlobalId, permission: String) -> Option<RateLimit> {
+            let permission = crate::access_manager::access_manager_helper::parse_permissions(vec![permission]).remove(0);
+            crate::access_manager::access_manager_helper::effective_rate_limit(self, &RateLimitedCaller::SignatureDelegate(delegate), &permission)
+        }
+        // dry-run introspection for the badge behind `proof`, the same checks the gated methods run
+        pub fn describe_key_permissions(&self, proof: NonFungibleProof) -> Vec<(KeyBadgePermission, Vec<String>)> {
+            crate::access_manager::access_manager_helper::describe_permissions(self, proof)
+        }
+        pub fn can_call(&self, method_name: String, proof: NonFungibleProof) -> bool {
+            crate::access_manager::access_manager_helper::caller_can_call(self, method_name.as_str(), proof)
+        }
+
         // private methods
-        fn internal_create_custom_access_key_badge(&mut self, permissions: Vec<KeyBadgePermission>) -> NonFungibleBucket {
+        fn create_custom_key(&mut self, permissions: Vec<String>, transfer_policy: KeyTransferPolicy, bound_account: Option<ComponentAddress>, proof: Option<NonFungibleProof>) -> NonFungibleBucket {
+            let permissions_vec = crate::access_manager::access_manager_helper::parse_permissions(permissions);
+            // if component_owner, accept super permissions as is
+            // if key holder, session key or signature delegate, super permissions must be none
+            let delegated_caller = match &proof {
+                Some(proof) => proof.resource_manager().address() != self.owner_badge_resource_manager.address(),
+                None => true
+            };
+            if delegated_caller {
+                // if the proof is an access key badge, make sure no super permissions are included
+                if permissions_vec.iter().any(|p| matches!(p, KeyBadgePermission::CreateAccessKey | KeyBadgePermission::RecallAccessKey)) {
+                    AccessManagerError::SuperPermissionForbidden.panic();
+                }
+            }
+
+            // can be called by either the owner or a key holder
+            // need to check if the key holder has the right permissions
+            crate::access_manager::access_manager_helper::check_caller_permissions(self, KeyBadgePermission::CreateAccessKey, Some(permissions_vec.clone()), proof);
+            
+            self.internal_create_custom_access_key_badge(permissions_vec, transfer_policy, bound_account)
+        }
+        fn internal_create_custom_access_key_badge(&mut self, permissions: Vec<KeyBadgePermission>, transfer_policy: KeyTransferPolicy, bound_account: Option<ComponentAddress>) -> NonFungibleBucket {
                     // if component_owner, accept super permissions as is
                     // if key holder, super permissions must be none
             let access_key_badge_data = AccessKeyBadgeData {
                 manager_component_address: Runtime::global_address(),
                 auth_badge_address: self.auth_badge.resource_address(),
-                permissions: permissions
+                permissions,
+                transfer_policy,
+                bound_account
             };
             self.access_key_badge_resource_manager.mint_ruid_non_fungible(access_key_badge_data).as_non_fungible()
         }
 
         // validator extension methods
-        pub fn validator_register(&mut self, proof: NonFungibleProof) {
+        pub fn validator_register(&mut self, proof: Option<NonFungibleProof>) {
             crate::validator_extension::validator_extension::register(self, proof);
         }
-        pub fn validator_unregister(&mut self, proof: NonFungibleProof) {
+        pub fn validator_unregister(&mut self, proof: Option<NonFungibleProof>) {
             crate::validator_extension::validator_extension::unregister(self, proof);
         }
-        pub fn validator_stake_as_owner(&mut self, stake: Bucket, proof: NonFungibleProof) -> Bucket {
+        pub fn validator_stake_as_owner(&mut self, stake: Bucket, proof: Option<NonFungibleProof>) -> Bucket {
             crate::validator_extension::validator_extension::stake_as_owner(self, stake, proof)
         }
-        pub fn validator_update_key(&mut self, key: Secp256k1PublicKey, proof: NonFungibleProof) {
+        pub fn validator_stake_as_owner_to_payout(&mut self, stake: Bucket, proof: Option<NonFungibleProof>) {
+            crate::validator_extension::validator_extension::stake_as_owner_to_payout(self, stake, proof);
+        }
+        pub fn validator_stake_from_treasury(&mut self, amount: Decimal, proof: Option<NonFungibleProof>) {
+            crate::validator_extension::validator_extension::stake_from_treasury(self, amount, proof);
+        }
+        pub fn validator_update_key(&mut self, key: Secp256k1PublicKey, proof: Option<NonFungibleProof>) {
             crate::validator_extension::validator_extension::update_key(self, key, proof);
         }
-        pub fn validator_update_fee(&mut self, new_fee_factor: Decimal, proof: NonFungibleProof) {
+        pub fn propose_validator_operation(&mut self, operation: ValidatorOperation, proof: Option<NonFungibleProof>) -> u64 {
+            crate::validator_extension::validator_extension::propose_operation(self, operation, proof)
+        }
+        pub fn execute_validator_proposal(&mut self, proposal_id: u64) {
+            crate::validator_extension::validator_extension::execute_proposal(self, proposal_id);
+        }
+        pub fn veto_validator_proposal(&mut self, proposal_id: u64, proof: Option<NonFungibleProof>) {
+            crate::validator_extension::validator_extension::veto_proposal(self, proposal_id, proof);
+        }
+        pub fn validator_update_fee(&mut self, new_fee_factor: Decimal, proof: Option<NonFungibleProof>) {
             crate::validator_extension::validator_extension::update_fee(self, new_fee_factor, proof);
         }
-        pub fn validator_update_accept_delegated_stake(&mut self, accept_delegated_stake: bool, proof: NonFungibleProof) {
+        pub fn validator_update_accept_delegated_stake(&mut self, accept_delegated_stake: bool, proof: Option<NonFungibleProof>) {
             crate::validator_extension::validator_extension::update_accept_delegated_stake(self, accept_delegated_stake, proof);
         }
-        pub fn validator_signal_protocol_update_readiness(&mut self, vote: String, proof: NonFungibleProof) {
+        pub fn validator_signal_protocol_update_readiness(&mut self, vote: String, proof: Option<NonFungibleProof>) {
             crate::validator_extension::validator_extension::signal_protocol_update_readiness(self, vote, proof);
         }
-        pub fn validator_lock_owner_stake_units(&mut self, stake_unit_bucket: Bucket, proof: NonFungibleProof) {
+        pub fn validator_lock_owner_stake_units(&mut self, stake_unit_bucket: Bucket, proof: Option<NonFungibleProof>) {
             crate::validator_extension::validator_extension::lock_owner_stake_units(self, stake_unit_bucket, proof);
         }
-        pub fn validator_start_unlock_owner_stake_units(&mut self, requested_stake_unit_amount: Decimal, proof: NonFungibleProof) {
+        pub fn validator_lock_owner_stake_units_from_treasury(&mut self, amount: Decimal, proof: Option<NonFungibleProof>) {
+            crate::validator_extension::validator_extension::lock_owner_stake_units_from_treasury(self, amount, proof);
+        }
+        pub fn validator_start_unlock_owner_stake_units(&mut self, requested_stake_unit_amount: Decimal, proof: Option<NonFungibleProof>) {
             crate::validator_extension::validator_extension::start_unlock_owner_stake_units(self, requested_stake_unit_amount, proof);
         }
-        pub fn validator_finish_unlock_owner_stake_units(&mut self, proof: NonFungibleProof) -> Bucket {
+        pub fn validator_finish_unlock_owner_stake_units(&mut self, proof: Option<NonFungibleProof>) -> Bucket {
             crate::validator_extension::validator_extension::finish_unlock_owner_stake_units(self, proof)
         }
+        pub fn validator_finish_unlock_owner_stake_units_to_payout(&mut self, proof: Option<NonFungibleProof>) {
+            crate::validator_extension::validator_extension::finish_unlock_owner_stake_units_to_payout(self, proof);
+        }
+        pub fn validator_compound_owner_stake(&mut self, proof: Option<NonFungibleProof>) {
+            crate::validator_extension::validator_extension::compound_owner_stake(self, proof);
+        }
+        pub fn validator_set_metadata(&mut self, key: String, value: String, proof: Option<NonFungibleProof>) {
+            crate::validator_extension::validator_extension::set_metadata(self, key, value, proof);
+        }
+        pub fn validator_lock_metadata(&mut self, key: String, proof: Option<NonFungibleProof>) {
+            crate::validator_extension::validator_extension::lock_metadata(self, key, proof);
+        }
+        pub fn validator_set_metadata_role(&mut self, role_name: String, rule: AccessRule, proof: Option<NonFungibleProof>) {
+            crate::validator_extension::validator_extension::set_metadata_role(self, role_name, rule, proof);
+        }
     }
 }
@@ -1,40 +1,526 @@
 use scrypto::prelude::*;
 use crate::access_manager::access_manager::access_manager::AccessManager;
 use crate::access_manager::custom_types::*;
+use crate::access_manager::errors::{ensure, AccessManagerError};
+use crate::access_manager::events::*;
+use strum::IntoEnumIterator;
+
+///
+/// The badge behind a proof passed to the component.
+///
+pub(crate) enum CallerBadge {
+    Owner,
+    AccessKey(NonFungibleLocalId, AccessKeyBadgeData),
+    // a transaction-scoped key minted by `create_session_key`
+    SessionKey(AccessKeyBadgeData),
+    // no proof was passed, the caller can only be a registered signature delegate. `check_caller_permissions` fills in
+    // the delegates that may have signed, see `assert_signature_delegate`
+    SignatureDelegate(Vec<NonFungibleGlobalId>),
+    Unknown
+}
+///
+/// Identifies the badge behind `proof`, validating it against the owner badge, access key badge or session key badge resource.
+/// Without a proof the caller is `CallerBadge::SignatureDelegate`, proofs of any other resource are `CallerBadge::Unknown`.
+/// A key with a `bound_account` is only accepted if the auth zone also proves control of that account, see `assert_account_controlled`.
+/// The proof is dropped once read, so the badge behind it is no longer locked (e.g. for `transfer_key_badge`, which recalls it).
+///
+pub(crate) fn identify_caller(access_manager: &AccessManager, proof: Option<NonFungibleProof>) -> CallerBadge {
+    let caller = identify_badge(access_manager, proof);
+    if let CallerBadge::AccessKey(_, access_key_data) = &caller {
+        if let Some(bound_account) = access_key_data.bound_account {
+            assert_account_controlled(bound_account);
+        }
+    }
+    caller
+}
+///
+/// `identify_caller` without the bound account check, for the views: asserting it would make them panic instead of answering.
+///
+fn identify_badge(access_manager: &AccessManager, proof: Option<NonFungibleProof>) -> CallerBadge {
+    let Some(proof) = proof else {
+        return CallerBadge::SignatureDelegate(vec![]);
+    };
+    if proof.resource_manager().address() == access_manager.owner_badge_resource_manager.address() {
+        let checked_proof = proof.check(access_manager.owner_badge_resource_manager.address());
+        let _owner_badge_data = checked_proof.non_fungible::<OwnerBadgeData>().data();
+        checked_proof.drop();
+        CallerBadge::Owner
+    }
+    else if proof.resource_manager().address() == access_manager.access_key_badge_resource_manager.address() {
+        let checked_proof = proof.check(access_manager.access_key_badge_resource_manager.address());
+        let access_key = checked_proof.non_fungible::<AccessKeyBadgeData>();
+        checked_proof.drop();
+        CallerBadge::AccessKey(access_key.local_id().clone(), access_key.data())
+    }
+    else if proof.resource_manager().address() == access_manager.session_key_badge_resource_manager.address() {
+        let checked_proof = proof.check(access_manager.session_key_badge_resource_manager.address());
+        let session_key_data = checked_proof.non_fungible::<AccessKeyBadgeData>().data();
+        checked_proof.drop();
+        CallerBadge::SessionKey(session_key_data)
+    }
+    else {
+        CallerBadge::Unknown
+    }
+}
+///
+/// Asserts the auth zone satisfies the owner role of `account`, the rule the account itself checks before a withdrawal.
+/// A leaked key proof presented from any other account fails here, with the engine's `AssertAccessRuleFailed` rather than an AM code.
+///
+fn assert_account_controlled(account: ComponentAddress) {
+    let account_component: Global<Account> = account.into();
+    Runtime::assert_access_rule(account_component.get_owner_role().rule);
+}
+///
+/// Asserts the auth zone holds the signature of a registered signature delegate that qualifies, i.e. `charged_permissions` returns the permissions its use is charged to.
+/// A delegate granting a permission rate limited for it when minting a key does not qualify, see `check_caller_permissions`.
+/// Fails with `NotAuthorized` if no registered delegate qualifies, with `RateLimitExceeded` if every qualifying delegate used up the limit of one of its charged permissions,
+/// and with `AssertAccessRuleFailed` if none of the remaining delegates signed. Returns the remaining delegates, one of which signed.
+///
+/// The auth zone does not tell which of the remaining delegates signed, so the use is charged to each of them: none can exceed its own limit,
+/// but delegates qualifying for the same call share their uses. Owners wanting separate budgets register delegates with disjoint permissions.
+///
+fn assert_signature_delegate(access_manager: &AccessManager, charged_permissions: impl Fn(&RateLimitedCaller, &Vec<KeyBadgePermission>) -> Option<Vec<KeyBadgePermission>>) -> Vec<NonFungibleGlobalId> {
+    let qualifying: Vec<(RateLimitedCaller, Vec<KeyBadgePermission>)> = access_manager
+        .signature_delegates
+        .iter()
+        .filter_map(|(delegate, permissions)| {
+            let delegate = RateLimitedCaller::SignatureDelegate(delegate.clone());
+            charged_permissions(&delegate, permissions).map(|charged| (delegate, charged))
+        })
+        .collect();
+    ensure(!qualifying.is_empty(), AccessManagerError::NotAuthorized);
+    let within_limits: Vec<(RateLimitedCaller, Vec<KeyBadgePermission>)> = qualifying
+        .into_iter()
+        .filter(|(delegate, charged)| charged.iter().all(|permission| has_calls_left(access_manager, delegate, permission)))
+        .collect();
+    ensure(!within_limits.is_empty(), AccessManagerError::RateLimitExceeded);
+    let delegates: Vec<NonFungibleGlobalId> = within_limits
+        .iter()
+        .filter_map(|(delegate, _)| match delegate {
+            RateLimitedCaller::SignatureDelegate(delegate) => Some(delegate.clone()),
+            RateLimitedCaller::AccessKey(_) => None
+        })
+        .collect();
+    let signers: Vec<ResourceOrNonFungible> = delegates.iter().map(|delegate| delegate.clone().into()).collect();
+    Runtime::assert_access_rule(rule!(require_any_of(signers)));
+    for (delegate, charged) in within_limits {
+        charged.iter().for_each(|permission| use_rate_limit(access_manager, &delegate, permission));
+    }
+    delegates
+}
+///
+/// Whether `delegate` is the virtual badge of a Secp256k1 or Ed25519 signature, the only badges accepted as signature delegates.
+///
+pub(crate) fn is_signature_badge(delegate: &NonFungibleGlobalId) -> bool {
+    delegate.resource_address() == SECP256K1_SIGNATURE_VIRTUAL_BADGE || delegate.resource_address() == ED25519_SIGNATURE_VIRTUAL_BADGE
+}
+///
+/// Parses permission names as given to `create_custom_access_key_badge`, panics with `InvalidPermissionString` naming the first invalid one.
+///
+pub(crate) fn parse_permissions(permissions: Vec<String>) -> Vec<KeyBadgePermission> {
+    let permissions_enum: Result<Vec<_>, _> = permissions
+        .into_iter()
+        .map(|s| KeyBadgePermission::from_str(&s).map_err(|e| format!("{} ({})", s, e)))
+        .collect();
+    permissions_enum.unwrap_or_else(|e| AccessManagerError::InvalidPermissionString.panic_with(e))
+}
 ///
 /// This function checks if the caller has the required permissions to perform an action.
 /// It checks if the caller has the owner badge or an access key badge with the required permissions.
-/// If the caller does not have the required permissions, it panics with an error message.
+/// If the caller does not have the required permissions, it panics with `AccessManagerError::NotAuthorized`.
 /// If the caller is a key holder and the required permission is `CreateAccessKey`, create_badge_permissions must be present to also checks if the key holder has the same permission they are granting.
-/// 
-pub(crate) fn check_caller_permissions(access_manager: &AccessManager, required_permission: KeyBadgePermission, create_badge_permissions: Option<Vec<KeyBadgePermission>>, proof: NonFungibleProof) {
-    let has_permission: bool;
-    if proof.resource_manager().address() == access_manager.owner_badge_resource_manager.address() {
-        let _owner_badge_data = proof.check(access_manager.owner_badge_resource_manager.address()).non_fungible::<OwnerBadgeData>().data();
-        // if the proof is the owner badge, he has permission, proceed
-        has_permission = true;
+/// A fresh key starts with fresh rate limit windows, so key holders cannot grant a permission rate limited for their key and signature delegates
+/// granting a permission rate limited for them do not qualify.
+/// Without a proof, a signature delegate holding the required (and granted) permissions must have signed the transaction.
+/// Uses by long-lived keys and signature delegates count against their rate limit of `required_permission`, see `use_rate_limit`.
+/// Returns the caller for methods that record who called them, a signature delegate with the delegates that may have signed.
+///
+pub(crate) fn check_caller_permissions(access_manager: &AccessManager, required_permission: KeyBadgePermission, create_badge_permissions: Option<Vec<KeyBadgePermission>>, proof: Option<NonFungibleProof>) -> CallerBadge {
+    let caller = identify_caller(access_manager, proof);
+    match &caller {
+        // the owner has every permission, proceed
+        CallerBadge::Owner => {}
+        CallerBadge::AccessKey(_, access_key_data) | CallerBadge::SessionKey(access_key_data) => {
+            if !access_key_data.permissions.contains(&required_permission) {
+                AccessManagerError::NotAuthorized.panic();
+            }
+            if required_permission == KeyBadgePermission::CreateAccessKey {
+                // the key holder must have the same permission he is giving as well
+                let create_badge_permissions = create_badge_permissions.unwrap_or_else(|| AccessManagerError::GrantedPermissionsNotProvided.panic());
+                create_badge_permissions.iter().for_each(|permission| {
+                    if !access_key_data.permissions.contains(permission) {
+                        AccessManagerError::MissingGrantedPermission.panic_with(permission);
+                    }
+                    // session keys are not rate limited, they never hold `CreateAccessKey`
+                    if let CallerBadge::AccessKey(key_id, _) = &caller {
+                        if effective_rate_limit(access_manager, &RateLimitedCaller::AccessKey(key_id.clone()), permission).is_some() {
+                            AccessManagerError::RateLimitedGrantedPermission.panic_with(permission);
+                        }
+                    }
+                });
+            }
+        }
+        CallerBadge::SignatureDelegate(_) => {
+            let delegates = assert_signature_delegate(access_manager, |delegate, permissions| {
+                let qualifies = permissions.contains(&required_permission) && create_badge_permissions.iter().flatten().all(|permission| {
+                    permissions.contains(permission) && effective_rate_limit(access_manager, delegate, permission).is_none()
+                });
+                qualifies.then(|| vec![required_permission.clone()])
+            });
+            return CallerBadge::SignatureDelegate(delegates);
+        }
+        CallerBadge::Unknown => AccessManagerError::NotAuthorized.panic()
     }
-    else if proof.resource_manager().address() == access_manager.access_key_badge_resource_manager.address() {
-        // if the proof is an access key badge, check if it has the right permissions
-        let access_key_data = proof.check(access_manager.access_key_badge_resource_manager.address()).non_fungible::<AccessKeyBadgeData>().data();
-        has_permission = access_key_data.permissions.contains(&required_permission);
-        if required_permission == KeyBadgePermission::CreateAccessKey {
-            // the key holder must have the same permission he is giving as well
-            if create_badge_permissions.is_none() {
-                panic!("Key holder must provide the permissions they are granting when creating a new access key badge!");
+    if let CallerBadge::AccessKey(key_id, _) = &caller {
+        use_rate_limit(access_manager, &RateLimitedCaller::AccessKey(key_id.clone()), &required_permission);
+    }
+    caller
+}
+///
+/// Same as `check_caller_permissions`, but a key holder passes if their key has any one of `required_permissions`.
+/// Used where a broad permission and a narrower scoped permission both grant access to the same action.
+///
+/// A use counts against the rate limit of every one of `required_permissions` the caller holds, so holding the broad permission
+/// next to a rate limited scoped one does not lift the scoped limit.
+///
+pub(crate) fn check_caller_any_permission(access_manager: &AccessManager, required_permissions: Vec<KeyBadgePermission>, proof: Option<NonFungibleProof>) {
+    let held_permissions = |permissions: &Vec<KeyBadgePermission>| -> Vec<KeyBadgePermission> {
+        required_permissions.iter().filter(|permission| permissions.contains(permission)).cloned().collect()
+    };
+    match identify_caller(access_manager, proof) {
+        CallerBadge::Owner => {}
+        CallerBadge::AccessKey(key_id, access_key_data) => {
+            let held = held_permissions(&access_key_data.permissions);
+            ensure(!held.is_empty(), AccessManagerError::NotAuthorized);
+            let caller = RateLimitedCaller::AccessKey(key_id);
+            held.iter().for_each(|permission| use_rate_limit(access_manager, &caller, permission));
+        }
+        CallerBadge::SessionKey(access_key_data) => ensure(!held_permissions(&access_key_data.permissions).is_empty(), AccessManagerError::NotAuthorized),
+        CallerBadge::SignatureDelegate(_) => {
+            assert_signature_delegate(access_manager, |_, permissions| {
+                let held = held_permissions(permissions);
+                (!held.is_empty()).then_some(held)
+            });
+        }
+        CallerBadge::Unknown => AccessManagerError::NotAuthorized.panic()
+    }
+}
+///
+/// The rate limit `caller` is held to for `permission`: the key's or delegate's override if the owner set one, otherwise the component-wide limit.
+///
+pub(crate) fn effective_rate_limit(access_manager: &AccessManager, caller: &RateLimitedCaller, permission: &KeyBadgePermission) -> Option<RateLimit> {
+    if let Some(rate_limit) = access_manager.rate_limit_overrides.get(&(caller.clone(), permission.clone())) {
+        return Some(*rate_limit);
+    }
+    access_manager.rate_limits.get(permission).copied()
+}
+///
+/// Sets or, with `None`, removes the override of the rate limit of `permission` for `caller`, see `set_key_rate_limit` and `set_delegate_rate_limit`.
+///
+pub(crate) fn set_rate_limit_override(access_manager: &AccessManager, caller: RateLimitedCaller, permission: String, rate_limit: Option<RateLimit>) {
+    let permission = parse_permissions(vec![permission]).remove(0);
+    match rate_limit {
+        Some(rate_limit) => {
+            validate_rate_limit(&rate_limit);
+            access_manager.rate_limit_overrides.insert((caller, permission), rate_limit);
+        }
+        None => {
+            access_manager.rate_limit_overrides.remove(&(caller, permission));
+        }
+    }
+}
+pub(crate) fn clear_rate_limit_overrides(access_manager: &AccessManager, caller: &RateLimitedCaller) {
+    for permission in KeyBadgePermission::iter() {
+        access_manager.rate_limit_overrides.remove(&(caller.clone(), permission));
+    }
+}
+pub(crate) fn validate_rate_limit(rate_limit: &RateLimit) {
+    let length = match rate_limit.window {
+        RateLimitWindow::Epochs(length) | RateLimitWindow::Seconds(length) => length
+    };
+    ensure(length > 0, AccessManagerError::InvalidRateLimit);
+}
+// the index of the window the current epoch or ledger time falls in
+fn current_window_index(window: RateLimitWindow) -> u64 {
+    match window {
+        RateLimitWindow::Epochs(length) => Runtime::current_epoch().number() / length,
+        RateLimitWindow::Seconds(length) => Clock::current_time_rounded_to_seconds().seconds_since_unix_epoch as u64 / length,
+    }
+}
+// the rate limit of `permission` for `caller` with its current window index and the uses counted in it, `None` without a limit
+fn current_usage(access_manager: &AccessManager, caller: &RateLimitedCaller, permission: &KeyBadgePermission) -> Option<(RateLimit, u64, u32)> {
+    let rate_limit = effective_rate_limit(access_manager, caller, permission)?;
+    let window_index = current_window_index(rate_limit.window);
+    let calls = match access_manager.rate_limit_usage.get(&(caller.clone(), permission.clone())) {
+        // a new window, or a changed limit, starts counting from zero
+        Some(usage) if usage.window == rate_limit.window && usage.window_index == window_index => usage.calls,
+        _ => 0
+    };
+    Some((rate_limit, window_index, calls))
+}
+fn has_calls_left(access_manager: &AccessManager, caller: &RateLimitedCaller, permission: &KeyBadgePermission) -> bool {
+    match current_usage(access_manager, caller, permission) {
+        Some((rate_limit, _, calls)) => calls < rate_limit.max_calls,
+        None => true,
+    }
+}
+///
+/// Counts a use of `permission` by `caller` in the usage store, panics with `RateLimitExceeded` once the current window is used up.
+/// The owner is never limited and session keys are not counted, they cannot carry permissions rate limited for their creator.
+///
+fn use_rate_limit(access_manager: &AccessManager, caller: &RateLimitedCaller, permission: &KeyBadgePermission) {
+    let Some((rate_limit, window_index, calls)) = current_usage(access_manager, caller, permission) else {
+        return;
+    };
+    ensure(calls < rate_limit.max_calls, AccessManagerError::RateLimitExceeded);
+    access_manager.rate_limit_usage.insert((caller.clone(), permission.clone()), RateLimitUsage { window: rate_limit.window, window_index, calls: calls + 1 });
+}
+
+// methods only the owner badge passes, `veto_validator_proposal` additionally accepts guardians from the auth zone
+const OWNER_ONLY_METHODS: [&str; 13] = [
+    "deposit_auth_badge", "create_super_access_key_badge", "withdraw_auth_badge", "set_payout_account", "register_signature_delegate", "remove_signature_delegate",
+    "set_rate_limit", "set_key_rate_limit", "set_delegate_rate_limit", "set_validator_operation_delay", "register_guardian", "remove_guardian", "veto_validator_proposal"
+];
+// methods any long-lived key may call, `transfer_key_badge` additionally checks the key's transfer policy
+const KEY_HOLDER_METHODS: [&str; 3] = ["burn_key_badge", "transfer_key_badge", "create_session_key"];
+// methods only the holder of a long-lived key may call, on their own key
+const KEY_ONLY_METHODS: [&str; 2] = ["renounce_permissions", "renounce_key"];
+// methods anyone may call
+const VIEW_METHODS: [&str; 14] = [
+    "get_auth_badge_info", "get_resource_addresses", "get_validator_address", "get_config", "get_signature_delegates", "get_rate_limits", "get_key_rate_limit",
+    "get_delegate_rate_limit", "get_validator_operation_delays", "get_guardians", "get_validator_proposals", "get_key_burned_at", "describe_key_permissions", "can_call"
+];
+// non-view methods anyone may call, a proposal was authorized when it was proposed
+const OPEN_METHODS: [&str; 1] = ["execute_validator_proposal"];
+///
+/// The permission-gated PUBLIC methods, each with the permissions that let a key holder call it.
+/// Must be kept in line with the `check_caller_permissions` calls, `tests/permission_matrix.rs` checks both against each other.
+///
+pub fn permission_gated_methods() -> Vec<(&'static str, Vec<KeyBadgePermission>)> {
+    use KeyBadgePermission::*;
+    vec![
+        ("create_basic_key_badge", vec![CreateAccessKey]),
+        ("create_custom_access_key_badge", vec![CreateAccessKey]),
+        ("create_custom_access_key_badge_with_policy", vec![CreateAccessKey]),
+        ("create_bound_access_key_badge", vec![CreateAccessKey]),
+        ("recall_key_badge", vec![RecallAccessKey]),
+        ("recall_key_badges_by_id", vec![RecallAccessKey]),
+        ("recall_and_burn_key_badges", vec![RecallAccessKey]),
+        ("create_auth_badge_proof", vec![CreateNativeProof]),
+        ("deposit_to_treasury", vec![Treasury_Deposit]),
+        ("withdraw_from_treasury", vec![Treasury_Withdraw]),
+        ("validator_register", vec![Validator_Register]),
+        ("validator_unregister", vec![Validator_Unregister]),
+        // the operation argument decides which of the two permissions is required
+        ("propose_validator_operation", vec![Validator_UpdateKey, Validator_Unregister]),
+        ("validator_stake_as_owner", vec![Validator_StakeAsOwner]),
+        ("validator_stake_as_owner_to_payout", vec![Validator_StakeAsOwnerToPayout]),
+        ("validator_stake_from_treasury", vec![Validator_StakeFromTreasury]),
+        ("validator_update_key", vec![Validator_UpdateKey]),
+        ("validator_update_fee", vec![Validator_UpdateFee]),
+        ("validator_update_accept_delegated_stake", vec![Validator_UpdateAcceptDelegatedStake]),
+        ("validator_signal_protocol_update_readiness", vec![Validator_SignalProtocolUpdateReadiness]),
+        ("validator_lock_owner_stake_units", vec![Validator_LockOwnerStakeUnits]),
+        ("validator_lock_owner_stake_units_from_treasury", vec![Validator_LockOwnerStakeUnitsFromTreasury]),
+        ("validator_start_unlock_owner_stake_units", vec![Validator_StartUnlockOwnerStakeUnits]),
+        ("validator_finish_unlock_owner_stake_units", vec![Validator_FinishUnlockOwnerStakeUnits]),
+        ("validator_finish_unlock_owner_stake_units_to_payout", vec![Validator_FinishUnlockOwnerStakeUnitsToPayout]),
+        ("validator_compound_owner_stake", vec![Validator_CompoundOwnerStake]),
+        // the scoped metadata permissions only allow their own key, see `validator_extension::set_metadata`
+        ("validator_set_metadata", vec![Validator_SetMetadata, Validator_SetMetadata_Name, Validator_SetMetadata_Description, Validator_SetMetadata_InfoUrl, Validator_SetMetadata_IconUrl]),
+        ("validator_lock_metadata", vec![Validator_LockMetadata]),
+        ("validator_set_metadata_role", vec![Validator_SetMetadataRole]),
+    ]
+}
+///
+/// The permissions the caller holds, each with the methods it unlocks. The owner holds every permission.
+/// Signature delegates are listed by `get_signature_delegates` instead, they have no badge to prove.
+///
+pub(crate) fn describe_permissions(access_manager: &AccessManager, proof: NonFungibleProof) -> Vec<(KeyBadgePermission, Vec<String>)> {
+    let permissions: Vec<KeyBadgePermission> = match identify_badge(access_manager, Some(proof)) {
+        CallerBadge::Owner => KeyBadgePermission::iter().collect(),
+        CallerBadge::AccessKey(_, access_key_data) | CallerBadge::SessionKey(access_key_data) => access_key_data.permissions,
+        CallerBadge::SignatureDelegate(_) | CallerBadge::Unknown => vec![]
+    };
+    let gated_methods = permission_gated_methods();
+    permissions
+        .into_iter()
+        .map(|permission| {
+            let methods = gated_methods
+                .iter()
+                .filter(|(_, granted_by)| granted_by.contains(&permission))
+                .map(|(method, _)| method.to_string())
+                .collect();
+            (permission, methods)
+        })
+        .collect()
+}
+///
+/// Whether the caller passes the permission check of `method_name`. Rules that depend on the arguments,
+/// such as holding every permission a new key is granted or the key of a scoped metadata permission, are only checked by the call itself.
+/// So is the account of a bound key: there is no way to test an access rule without failing the transaction.
+/// A delayed validator method is only callable with the owner badge, other callers propose it with `propose_validator_operation`.
+/// A long-lived key whose rate limit for the method is used up for the current window cannot call it.
+///
+pub(crate) fn caller_can_call(access_manager: &AccessManager, method_name: &str, proof: NonFungibleProof) -> bool {
+    if VIEW_METHODS.contains(&method_name) || OPEN_METHODS.contains(&method_name) {
+        return true;
+    }
+    let caller = identify_badge(access_manager, Some(proof));
+    if access_manager.validator_operation_delays.contains_key(method_name) {
+        return matches!(caller, CallerBadge::Owner);
+    }
+    if OWNER_ONLY_METHODS.contains(&method_name) {
+        return matches!(caller, CallerBadge::Owner);
+    }
+    if KEY_HOLDER_METHODS.contains(&method_name) {
+        return matches!(caller, CallerBadge::Owner | CallerBadge::AccessKey(..));
+    }
+    if KEY_ONLY_METHODS.contains(&method_name) {
+        return matches!(caller, CallerBadge::AccessKey(..));
+    }
+    let Some((_, granted_by)) = permission_gated_methods().into_iter().find(|(method, _)| *method == method_name) else {
+        return false;
+    };
+    match caller {
+        CallerBadge::Owner => true,
+        CallerBadge::AccessKey(key_id, access_key_data) => {
+            let caller = RateLimitedCaller::AccessKey(key_id);
+            let held: Vec<&KeyBadgePermission> = granted_by.iter().filter(|permission| access_key_data.permissions.contains(permission)).collect();
+            let within_limit = |permission: &&KeyBadgePermission| has_calls_left(access_manager, &caller, permission);
+            // a proposal is only charged to the permission its operation requires, `check_caller_any_permission` to every one held
+            if method_name == "propose_validator_operation" {
+                held.iter().any(within_limit)
+            } else {
+                !held.is_empty() && held.iter().all(within_limit)
             }
-            let create_badge_permissions = create_badge_permissions.unwrap();
-            create_badge_permissions.iter().for_each(|permission| {
+        }
+        CallerBadge::SessionKey(access_key_data) => granted_by.iter().any(|permission| access_key_data.permissions.contains(permission)),
+        CallerBadge::SignatureDelegate(_) | CallerBadge::Unknown => false
+    }
+}
+///
+/// Recalls exactly the key badges `ids` from `vault_address`, which must be a vault of the access key badge resource.
+///
+pub(crate) fn recall_key_badges(access_manager: &AccessManager, vault_address: InternalAddress, ids: IndexSet<NonFungibleLocalId>) -> NonFungibleBucket {
+    let recalled_bucket: NonFungibleBucket = scrypto_decode(&ScryptoVmV1Api::object_call_direct(
+        vault_address.as_node_id(),
+        NON_FUNGIBLE_VAULT_RECALL_NON_FUNGIBLES_IDENT,
+        scrypto_args!(ids.clone()),
+    )).unwrap_or_else(|error| AccessManagerError::NotAKeyBadge.panic_with(format!("{:?}", error)));
+    ensure(recalled_bucket.resource_address() == access_manager.access_key_badge_resource_manager.address(), AccessManagerError::NotAKeyBadge);
+    ensure(recalled_bucket.non_fungible_local_ids() == ids, AccessManagerError::NotAKeyBadge);
+    recalled_bucket
+}
+///
+/// Burns a bucket of this manager's access keys, recording the burned ids in the `burned_keys` registry and a `KeyBadgesBurnedEvent`, or of its session keys.
+///
+pub(crate) fn burn_key_badge(access_manager: &AccessManager, key_badge: NonFungibleBucket) {
+    let resource_address = key_badge.resource_address();
+    // session keys are burned at the end of every transaction that creates one, there is nothing to record
+    if resource_address == access_manager.session_key_badge_resource_manager.address() {
+        key_badge.burn();
+        return;
+    }
+    ensure(resource_address == access_manager.access_key_badge_resource_manager.address(), AccessManagerError::NotAKeyBadge);
+    let key_ids = burn_access_keys(access_manager, key_badge);
+    Runtime::emit_event(KeyBadgesBurnedEvent { key_ids });
+}
+///
+/// Burns access keys and records each id in the `burned_keys` registry, returns the burned ids.
+/// Every path that burns an access key goes through here, so `get_key_burned_at` knows all of them.
+///
+fn burn_access_keys(access_manager: &AccessManager, key_badges: NonFungibleBucket) -> Vec<NonFungibleLocalId> {
+    let key_ids: Vec<NonFungibleLocalId> = key_badges.non_fungible_local_ids().into_iter().collect();
+    key_badges.burn();
+    let burned_at = Clock::current_time_rounded_to_seconds();
+    key_ids.iter().for_each(|key_id| access_manager.burned_keys.insert(key_id.clone(), burned_at));
+    key_ids
+}
+///
+/// Recalls and burns the listed keys of every vault, recording them in the `burned_keys` registry,
+/// then emits a single `KeyBadgesRecalledAndBurnedEvent` for the batch.
+///
+pub(crate) fn recall_and_burn_key_badges(access_manager: &AccessManager, keys: Vec<(InternalAddress, Vec<NonFungibleLocalId>)>) {
+    let key_ids = keys
+        .into_iter()
+        .flat_map(|(vault_address, ids)| {
+            let key_badges = recall_key_badges(access_manager, vault_address, ids.into_iter().collect());
+            burn_access_keys(access_manager, key_badges)
+        })
+        .collect();
+    Runtime::emit_event(KeyBadgesRecalledAndBurnedEvent { key_ids });
+}
+///
+/// Moves `key_id` from `key_vault` into the `destination` account. A key holder may only move their own key and only if it is
+/// `KeyTransferPolicy::Transferable`, the owner may move any key. An account-bound key is rebound to `destination`.
+///
+pub(crate) fn transfer_key_badge(access_manager: &AccessManager, key_vault: InternalAddress, key_id: NonFungibleLocalId, destination: ComponentAddress, proof: NonFungibleProof) {
+    match identify_caller(access_manager, Some(proof)) {
+        CallerBadge::Owner => {}
+        CallerBadge::AccessKey(proof_key_id, access_key_data) => {
+            ensure(proof_key_id == key_id, AccessManagerError::NotAuthorized);
+            ensure(access_key_data.transfer_policy == KeyTransferPolicy::Transferable, AccessManagerError::KeyNotTransferable);
+        }
+        CallerBadge::SessionKey(_) | CallerBadge::SignatureDelegate(_) | CallerBadge::Unknown => AccessManagerError::NotAuthorized.panic()
+    }
+    let key_badge = recall_key_badges(access_manager, key_vault, indexset!(key_id.clone()));
+    if key_badge.non_fungible::<AccessKeyBadgeData>().data().bound_account.is_some() {
+        access_manager.access_key_badge_resource_manager.update_non_fungible_data(&key_id, "bound_account", Some(destination));
+    }
+    // the key badge depositor rule accepts this component as the global caller
+    let mut account: Global<Account> = destination.into();
+    account.try_deposit_or_abort(key_badge.into(), None);
+}
+///
+/// Checks the caller may mint a session key with `permissions`: the owner may grant any permission but `CreateAccessKey` and
+/// `RecallAccessKey`, a long-lived key only permissions it holds itself. Session keys never mint or recall keys, whoever derives them.
+/// Session key uses are not counted by rate limits, so a key may not pass on permissions rate limited for it. Session keys cannot
+/// derive further session keys.
+///
+pub(crate) fn check_session_key_permissions(access_manager: &AccessManager, permissions: &[KeyBadgePermission], proof: NonFungibleProof) {
+    let grants_super_permission = permissions.iter().any(|p| matches!(p, KeyBadgePermission::CreateAccessKey | KeyBadgePermission::RecallAccessKey));
+    match identify_caller(access_manager, Some(proof)) {
+        CallerBadge::Owner => ensure(!grants_super_permission, AccessManagerError::SuperPermissionForbidden),
+        CallerBadge::AccessKey(key_id, access_key_data) => {
+            ensure(!grants_super_permission, AccessManagerError::SuperPermissionForbidden);
+            permissions.iter().for_each(|permission| {
                 if !access_key_data.permissions.contains(permission) {
-                    panic!("Key holder must have the permissions he is granting when creating a new access key badge!, missing permission: {:?}", permission );
+                    AccessManagerError::MissingGrantedPermission.panic_with(permission);
+                }
+                if effective_rate_limit(access_manager, &RateLimitedCaller::AccessKey(key_id.clone()), permission).is_some() {
+                    AccessManagerError::RateLimitedSessionPermission.panic_with(permission);
                 }
             });
         }
+        CallerBadge::SessionKey(_) | CallerBadge::SignatureDelegate(_) | CallerBadge::Unknown => AccessManagerError::NotAuthorized.panic()
     }
-    else {
-        has_permission = false;
+}
+// the id and data of the long-lived key behind `proof`, anything else is rejected
+fn own_key(access_manager: &AccessManager, proof: NonFungibleProof) -> (NonFungibleLocalId, AccessKeyBadgeData) {
+    match identify_caller(access_manager, Some(proof)) {
+        CallerBadge::AccessKey(key_id, access_key_data) => (key_id, access_key_data),
+        _ => AccessManagerError::NotAuthorized.panic()
     }
-    if !has_permission {
-        panic!("You do not have permission to perform the required action!");
-    }
-}
\ No newline at end of file
+}
+///
+/// Removes `permissions` from the caller's own key through the mutable `permissions` field.
+///
+pub(crate) fn renounce_permissions(access_manager: &AccessManager, permissions: Vec<KeyBadgePermission>, proof: NonFungibleProof) {
+    let (key_id, access_key_data) = own_key(access_manager, proof);
+    let (renounced, remaining): (Vec<KeyBadgePermission>, Vec<KeyBadgePermission>) = access_key_data
+        .permissions
+        .into_iter()
+        .partition(|permission| permissions.contains(permission));
+    access_manager.access_key_badge_resource_manager.update_non_fungible_data(&key_id, "permissions", remaining.clone());
+    Runtime::emit_event(PermissionsRenouncedEvent { key_id, renounced, remaining });
+}
+///
+/// Recalls the caller's own key from `key_vault` and burns it.
+///
+pub(crate) fn renounce_key(access_manager: &AccessManager, key_vault: InternalAddress, proof: NonFungibleProof) {
+    let (key_id, access_key_data) = own_key(access_manager, proof);
+    let key_badge = recall_key_badges(access_manager, key_vault, indexset!(key_id.clone()));
+    burn_access_keys(access_manager, key_badge);
+    Runtime::emit_event(KeyRenouncedEvent { key_id, permissions: access_key_data.permissions });
+}
@@ -1,5 +1,5 @@
 use scrypto::prelude::*;
-use strum_macros::EnumString;
+use strum_macros::{Display, EnumIter, EnumString};
 
 #[derive(ScryptoSbor, NonFungibleData)]
 pub struct OwnerBadgeData {
@@ -12,9 +12,43 @@ pub struct AccessKeyBadgeData {
     pub auth_badge_address: ResourceAddress,
 
     #[mutable]
-    pub permissions: Vec<KeyBadgePermission>
+    pub permissions: Vec<KeyBadgePermission>,
+    pub transfer_policy: KeyTransferPolicy,
+    // the account the key must be presented from, rebound when the key is moved with `transfer_key_badge`
+    #[mutable]
+    pub bound_account: Option<ComponentAddress>
+}
+// Whether the holder of a key may move it to another account through `transfer_key_badge`, the owner can always move keys
+#[derive(ScryptoSbor, ManifestSbor, PartialEq, Debug, Clone, Copy)]
+pub enum KeyTransferPolicy {
+    Soulbound,
+    Transferable
+}
+// The auth badge vault, `local_id` is `None` until the auth badge is deposited
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct AuthBadgeInfo {
+    pub resource_address: ResourceAddress,
+    pub deposited: bool,
+    pub local_id: Option<NonFungibleLocalId>
+}
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct ResourceAddresses {
+    pub auth_badge: ResourceAddress,
+    pub owner_badge: ResourceAddress,
+    pub access_key_badge: ResourceAddress,
+    pub session_key_badge: ResourceAddress
 }
-#[derive(ScryptoSbor, PartialEq, EnumString, Debug, Clone)]
+// Everything `get_config` reports in a single call
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct AccessManagerConfig {
+    pub auth_badge: AuthBadgeInfo,
+    pub resources: ResourceAddresses,
+    pub validator: Option<ComponentAddress>,
+    pub payout_account: Option<ComponentAddress>,
+    pub xrd_treasury_amount: Decimal,
+    pub stake_unit_treasury: Option<(ResourceAddress, Decimal)>
+}
+#[derive(ScryptoSbor, PartialEq, Eq, Hash, EnumString, EnumIter, Display, Debug, Clone)]
 #[allow(non_camel_case_types)]
 pub enum KeyBadgePermission {
     // Super permissions
@@ -24,15 +58,97 @@ pub enum KeyBadgePermission {
     // Basic permissions
     CreateNativeProof,
 
+    // Treasury permissions
+    Treasury_Deposit,
+    Treasury_Withdraw,
+
     // Validator permissions
     Validator_Register,
     Validator_Unregister,
     Validator_StakeAsOwner,
+    Validator_StakeAsOwnerToPayout,
+    Validator_StakeFromTreasury,
     Validator_UpdateKey,
     Validator_UpdateFee,
     Validator_UpdateAcceptDelegatedStake,
     Validator_SignalProtocolUpdateReadiness,
     Validator_LockOwnerStakeUnits,
+    Validator_LockOwnerStakeUnitsFromTreasury,
     Validator_StartUnlockOwnerStakeUnits,
-    Validator_FinishUnlockOwnerStakeUnits
-}
\ No newline at end of file
+    Validator_FinishUnlockOwnerStakeUnits,
+    Validator_FinishUnlockOwnerStakeUnitsToPayout,
+    Validator_CompoundOwnerStake,
+    Validator_SetMetadata,
+    Validator_SetMetadata_Name,
+    Validator_SetMetadata_Description,
+    Validator_SetMetadata_InfoUrl,
+    Validator_SetMetadata_IconUrl,
+    Validator_LockMetadata,
+    Validator_SetMetadataRole
+}
+// At most `max_calls` uses of a permission per window, windows are aligned to multiples of their length
+#[derive(ScryptoSbor, ManifestSbor, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub max_calls: u32,
+    pub window: RateLimitWindow
+}
+#[derive(ScryptoSbor, ManifestSbor, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum RateLimitWindow {
+    Epochs(u64),
+    // ledger time, e.g. `Seconds(86400)` for a day
+    Seconds(u64)
+}
+// Whose uses a rate limit counts and whose override applies, a long-lived key or a registered signature delegate
+#[derive(ScryptoSbor, PartialEq, Eq, Debug, Clone)]
+pub enum RateLimitedCaller {
+    AccessKey(NonFungibleLocalId),
+    SignatureDelegate(NonFungibleGlobalId)
+}
+// The uses counted in the current window of a rate limit
+#[derive(ScryptoSbor, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct RateLimitUsage {
+    pub window: RateLimitWindow,
+    pub window_index: u64,
+    pub calls: u32
+}
+// A validator operation the owner may put behind a delay, delegates then propose it with `propose_validator_operation`
+#[derive(ScryptoSbor, ManifestSbor, PartialEq, Eq, Debug, Clone)]
+pub enum ValidatorOperation {
+    UpdateKey(Secp256k1PublicKey),
+    Unregister
+}
+impl ValidatorOperation {
+    // the direct method the delay applies to
+    pub fn method_name(&self) -> &'static str {
+        match self {
+            Self::UpdateKey(_) => "validator_update_key",
+            Self::Unregister => "validator_unregister",
+        }
+    }
+    pub fn permission(&self) -> KeyBadgePermission {
+        match self {
+            Self::UpdateKey(_) => KeyBadgePermission::Validator_UpdateKey,
+            Self::Unregister => KeyBadgePermission::Validator_Unregister,
+        }
+    }
+}
+// The methods whose calls `set_validator_operation_delay` can delay
+pub const DELAYABLE_VALIDATOR_METHODS: [&str; 2] = ["validator_update_key", "validator_unregister"];
+// The longest delay `set_validator_operation_delay` accepts, 30 days
+pub const MAX_VALIDATOR_OPERATION_DELAY_SECONDS: i64 = 30 * 24 * 60 * 60;
+// Who proposed a validator operation. Session keys cannot propose, they end with their transaction
+#[derive(ScryptoSbor, PartialEq, Eq, Debug, Clone)]
+pub enum Proposer {
+    Owner,
+    AccessKey(NonFungibleLocalId),
+    // the auth zone does not tell which delegate signed, so every delegate that qualified and may have signed is recorded
+    SignatureDelegate(Vec<NonFungibleGlobalId>)
+}
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct ValidatorProposal {
+    pub operation: ValidatorOperation,
+    pub proposer: Proposer,
+    pub proposed_at: Instant,
+    // ledger time, the owner or a guardian may veto until then. A delay set or raised later counts from `proposed_at` as well
+    pub executable_at: Instant
+}
@@ -0,0 +1,164 @@
+use scrypto::prelude::*;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+///
+/// Every reason an `AccessManager` call is rejected by the component itself.
+/// The code of a variant never changes and codes are never reused, so clients can match on them instead of on message text.
+///
+/// Failures panic with `[AM-<code> <Variant>] <message>`, optionally followed by `: <detail>`, for example
+/// `[AM-102 MissingGrantedPermission] Key holder must have the permissions they are granting: Validator_UpdateFee`.
+/// `AccessManagerError::parse` recovers the variant from a panic message.
+///
+/// Checks made with `Runtime::assert_access_rule` (signature delegates, bound key accounts, guardians) fail with the engine's
+/// `AssertAccessRuleFailed` instead, Scrypto cannot test an access rule without failing the transaction.
+///
+#[derive(PartialEq, Eq, EnumIter, Display, Debug, Clone, Copy)]
+pub enum AccessManagerError {
+    // Authorization, 1xx
+    NotAuthorized,
+    MissingGrantedPermission,
+    GrantedPermissionsNotProvided,
+    SuperPermissionForbidden,
+
+    // Input, 2xx
+    InvalidPermissionString,
+    InvalidSignatureDelegate,
+    SignatureDelegateNotRegistered,
+
+    // Auth badge vault, 3xx
+    AuthBadgeAlreadyPresent,
+    InvalidAuthBadgeAmount,
+    EmptyAuthVault,
+
+    // Validator, payout and treasury, 4xx
+    PayoutAccountNotConfigured,
+    ValidatorPoolUnitMissing,
+    TreasuryResourceNotAccepted,
+    TreasuryResourceNotHeld,
+    TreasuryHasNoStakeUnits,
+    NothingToCompound,
+
+    // Factory, 5xx
+    ManagerAlreadyRegistered,
+
+    // Key badges, 6xx
+    KeyNotTransferable,
+    NotAKeyBadge,
+
+    // Rate limits, 7xx
+    RateLimitExceeded,
+    InvalidRateLimit,
+    RateLimitedSessionPermission,
+    RateLimitedGrantedPermission,
+
+    // Validator proposals, 8xx
+    ProposalRequired,
+    ProposalNotFound,
+    ProposalNotExecutable,
+    InvalidProposalDelay,
+    GuardianNotRegistered,
+    ProposalDelayTooLong,
+    ProposerNotAuthorized,
+}
+
+impl AccessManagerError {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::NotAuthorized => 101,
+            Self::MissingGrantedPermission => 102,
+            Self::GrantedPermissionsNotProvided => 103,
+            Self::SuperPermissionForbidden => 104,
+            Self::InvalidPermissionString => 201,
+            Self::InvalidSignatureDelegate => 202,
+            Self::SignatureDelegateNotRegistered => 203,
+            Self::AuthBadgeAlreadyPresent => 301,
+            Self::InvalidAuthBadgeAmount => 302,
+            Self::EmptyAuthVault => 303,
+            Self::PayoutAccountNotConfigured => 401,
+            Self::ValidatorPoolUnitMissing => 402,
+            Self::TreasuryResourceNotAccepted => 403,
+            Self::TreasuryResourceNotHeld => 404,
+            Self::TreasuryHasNoStakeUnits => 405,
+            Self::NothingToCompound => 406,
+            Self::ManagerAlreadyRegistered => 501,
+            Self::KeyNotTransferable => 601,
+            Self::NotAKeyBadge => 602,
+            Self::RateLimitExceeded => 701,
+            Self::InvalidRateLimit => 702,
+            Self::RateLimitedSessionPermission => 703,
+            Self::RateLimitedGrantedPermission => 704,
+            Self::ProposalRequired => 801,
+            Self::ProposalNotFound => 802,
+            Self::ProposalNotExecutable => 803,
+            Self::InvalidProposalDelay => 804,
+            Self::GuardianNotRegistered => 805,
+            Self::ProposalDelayTooLong => 806,
+            Self::ProposerNotAuthorized => 807,
+        }
+    }
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::NotAuthorized => "You do not have permission to perform the required action!",
+            Self::MissingGrantedPermission => "Key holder must have the permissions they are granting",
+            Self::GrantedPermissionsNotProvided => "Key holder must provide the permissions they are granting when creating a new access key badge!",
+            Self::SuperPermissionForbidden => "Key holders cannot create access key badges with super permissions!",
+            Self::InvalidPermissionString => "Invalid permission string",
+            Self::InvalidSignatureDelegate => "Only Secp256k1 or Ed25519 signature badges can be registered as signature delegates!",
+            Self::SignatureDelegateNotRegistered => "This signature badge is not a registered signature delegate!",
+            Self::AuthBadgeAlreadyPresent => "Access Manager component already has an auth badge!",
+            Self::InvalidAuthBadgeAmount => "Cannot deposit any amount other than exactly one!",
+            Self::EmptyAuthVault => "The auth badge vault is empty, deposit the auth badge first!",
+            Self::PayoutAccountNotConfigured => "No payout account is configured, the owner must call `set_payout_account` first!",
+            Self::ValidatorPoolUnitMissing => "Validator has no pool_unit metadata!",
+            Self::TreasuryResourceNotAccepted => "Only XRD and the validator's stake units can be deposited to the treasury!",
+            Self::TreasuryResourceNotHeld => "The treasury does not hold this resource!",
+            Self::TreasuryHasNoStakeUnits => "The treasury does not hold any stake units!",
+            Self::NothingToCompound => "There is no unlocked owner stake to compound!",
+            Self::ManagerAlreadyRegistered => "An Access Manager is already registered for this auth badge!",
+            Self::KeyNotTransferable => "This key badge is soulbound, only the owner can move it!",
+            Self::NotAKeyBadge => "The badge is not an access key badge of this Access Manager!",
+            Self::RateLimitExceeded => "The rate limit of this permission is used up for the current window!",
+            Self::InvalidRateLimit => "A rate limit window must be at least one epoch or one second long!",
+            Self::RateLimitedSessionPermission => "Session keys cannot carry a permission that is rate limited for the key creating them!",
+            Self::RateLimitedGrantedPermission => "Key holders cannot grant a permission that is rate limited for their key!",
+            Self::ProposalRequired => "This validator operation is delayed, propose it with `propose_validator_operation`!",
+            Self::ProposalNotFound => "There is no pending validator proposal with this id!",
+            Self::ProposalNotExecutable => "The delay of this validator proposal has not passed yet",
+            Self::InvalidProposalDelay => "Only validator_update_key and validator_unregister can be delayed, by a non-negative number of seconds!",
+            Self::GuardianNotRegistered => "This badge is not a registered guardian!",
+            Self::ProposalDelayTooLong => "A validator operation can be delayed by at most 30 days!",
+            Self::ProposerNotAuthorized => "The proposer of this validator proposal no longer holds the permission of the operation",
+        }
+    }
+    // `[AM-<code> <Variant>]`, the stable prefix of every panic message
+    pub fn tag(&self) -> String {
+        format!("[AM-{} {}]", self.code(), self)
+    }
+    pub fn from_code(code: u16) -> Option<Self> {
+        Self::iter().find(|error| error.code() == code)
+    }
+    ///
+    /// Finds the error tag in a panic message (the engine may add its own prefix) and returns the matching variant.
+    ///
+    pub fn parse(panic_message: &str) -> Option<Self> {
+        let start = panic_message.find("[AM-")? + "[AM-".len();
+        let code = panic_message[start..].get(..3)?.parse().ok()?;
+        Self::from_code(code)
+    }
+    pub fn panic(self) -> ! {
+        panic!("{} {}", self.tag(), self.message())
+    }
+    pub fn panic_with(self, detail: impl std::fmt::Display) -> ! {
+        panic!("{} {}: {}", self.tag(), self.message(), detail)
+    }
+}
+
+///
+/// `assert!` counterpart that panics with `error` in the documented format.
+///
+pub(crate) fn ensure(condition: bool, error: AccessManagerError) {
+    if !condition {
+        error.panic()
+    }
+}
@@ -0,0 +1,43 @@
+use scrypto::prelude::*;
+use crate::access_manager::custom_types::{KeyBadgePermission, ValidatorProposal};
+
+// A key holder dropped permissions from their own key with `renounce_permissions`
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct PermissionsRenouncedEvent {
+    pub key_id: NonFungibleLocalId,
+    pub renounced: Vec<KeyBadgePermission>,
+    pub remaining: Vec<KeyBadgePermission>
+}
+// A key holder resigned with `renounce_key`, the key was burned
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct KeyRenouncedEvent {
+    pub key_id: NonFungibleLocalId,
+    pub permissions: Vec<KeyBadgePermission>
+}
+// Access keys were burned through `burn_key_badge`, session keys are not recorded
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct KeyBadgesBurnedEvent {
+    pub key_ids: Vec<NonFungibleLocalId>
+}
+// Keys were recalled and burned with `recall_and_burn_key_badges`, one event for the whole batch
+// events cannot carry vault addresses, the vaults are in the transaction's manifest
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct KeyBadgesRecalledAndBurnedEvent {
+    pub key_ids: Vec<NonFungibleLocalId>
+}
+// A delayed validator operation was proposed with `propose_validator_operation`
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct ValidatorOperationProposedEvent {
+    pub proposal_id: u64,
+    pub proposal: ValidatorProposal
+}
+// A validator proposal was executed after its delay
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct ValidatorProposalExecutedEvent {
+    pub proposal_id: u64
+}
+// The owner or a guardian vetoed a validator proposal
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct ValidatorProposalVetoedEvent {
+    pub proposal_id: u64
+}
@@ -1,3 +1,6 @@
+#[allow(clippy::module_inception)]
 pub mod access_manager;
 pub mod access_manager_helper;
-pub mod custom_types;
\ No newline at end of file
+pub mod custom_types;
+pub mod errors;
+pub mod events;
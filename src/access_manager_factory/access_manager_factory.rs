@@ -0,0 +1,111 @@
+use scrypto::prelude::*;
+use crate::access_manager::access_manager::access_manager::{AccessManager, AccessManagerFunctions};
+use crate::access_manager::errors::{ensure, AccessManagerError};
+
+#[blueprint]
+mod access_manager_factory {
+
+    enable_function_auth! {
+        instantiate => rule!(allow_all);
+    }
+    enable_method_auth! {
+        methods {
+            new_manager => PUBLIC;
+            get_manager_by_auth_badge => PUBLIC;
+            get_manager_by_owner_badge => PUBLIC;
+            get_manager_count => PUBLIC;
+        }
+    }
+    enable_package_royalties! {
+        instantiate => Usd(dec!(0.01));
+        new_manager => Usd(dec!(0.01));
+        get_manager_by_auth_badge => Free;
+        get_manager_by_owner_badge => Free;
+        get_manager_count => Free;
+    }
+    pub struct AccessManagerFactory {
+        // reject a second manager for an auth badge whose registered manager still holds it
+        prevent_duplicates: bool,
+        // the manager each auth badge was deposited into at creation, so wallets can find the manager of a validator owner badge
+        // a registration is stale once its manager no longer holds the badge
+        managers_by_auth_badge: KeyValueStore<NonFungibleGlobalId, ComponentAddress>,
+        // owner badges are transferable, so managers are registered by owner badge resource rather than by account
+        // the auth badge is kept next to the manager, the registration is stale under the same condition
+        managers_by_owner_badge: KeyValueStore<ResourceAddress, (ComponentAddress, NonFungibleGlobalId)>,
+        manager_count: u64,
+    }
+    impl AccessManagerFactory {
+        pub fn instantiate(prevent_duplicates: bool, dapp_definition_address: ComponentAddress) -> Global<AccessManagerFactory> {
+            Self {
+                prevent_duplicates,
+                managers_by_auth_badge: KeyValueStore::new(),
+                managers_by_owner_badge: KeyValueStore::new(),
+                manager_count: 0
+            }
+            .instantiate()
+            .prepare_to_globalize(OwnerRole::None)
+            .metadata(metadata! (
+                init {
+                    "name" => "Access Manager Factory", locked;
+                    "dapp_definition" => dapp_definition_address, locked;
+                }
+            ))
+            .globalize()
+        }
+
+        ///
+        /// Instantiates an `AccessManager` with `AccessManager::new_with_auth_badge`, so only a holder of the badge can register a manager for it.
+        /// Returns the manager and its owner badge.
+        ///
+        pub fn new_manager(&mut self, auth_badge: NonFungibleBucket, dapp_definition_address: ComponentAddress) -> (Global<AccessManager>, NonFungibleBucket) {
+            let auth_badge_address = auth_badge.resource_address();
+            let auth_badge_ids = auth_badge.non_fungible_local_ids();
+            // rejects anything but exactly one badge with `InvalidAuthBadgeAmount`
+            let (manager, owner_badge) = Blueprint::<AccessManager>::new_with_auth_badge(auth_badge, dapp_definition_address);
+
+            let auth_badge_id = NonFungibleGlobalId::new(auth_badge_address, auth_badge_ids[0].clone());
+            if self.prevent_duplicates {
+                let registered = self.managers_by_auth_badge.get(&auth_badge_id).map(|manager| *manager);
+                ensure(!registered.is_some_and(|manager| Self::holds_auth_badge(manager, &auth_badge_id)), AccessManagerError::ManagerAlreadyRegistered);
+            }
+
+            // the latest manager replaces a stale registration, and any registration without duplicate prevention
+            self.managers_by_auth_badge.insert(auth_badge_id.clone(), manager.address());
+            self.managers_by_owner_badge.insert(owner_badge.resource_address(), (manager.address(), auth_badge_id));
+            self.manager_count += 1;
+
+            (manager, owner_badge)
+        }
+
+        // lookups
+        ///
+        /// The manager registered for `auth_badge`, or `None` once that manager no longer holds the badge.
+        ///
+        pub fn get_manager_by_auth_badge(&self, auth_badge: NonFungibleGlobalId) -> Option<ComponentAddress> {
+            self.managers_by_auth_badge
+                .get(&auth_badge)
+                .map(|manager| *manager)
+                .filter(|manager| Self::holds_auth_badge(*manager, &auth_badge))
+        }
+        ///
+        /// The manager of the owner badge resource `owner_badge`, or `None` once that manager no longer holds its auth badge.
+        ///
+        pub fn get_manager_by_owner_badge(&self, owner_badge: ResourceAddress) -> Option<ComponentAddress> {
+            self.managers_by_owner_badge
+                .get(&owner_badge)
+                .map(|registration| registration.clone())
+                .filter(|(manager, auth_badge)| Self::holds_auth_badge(*manager, auth_badge))
+                .map(|(manager, _)| manager)
+        }
+        pub fn get_manager_count(&self) -> u64 {
+            self.manager_count
+        }
+
+        // the owner can withdraw the auth badge at any time, so custody is checked on the manager itself
+        fn holds_auth_badge(manager: ComponentAddress, auth_badge: &NonFungibleGlobalId) -> bool {
+            let manager: Global<AccessManager> = manager.into();
+            let info = manager.get_auth_badge_info();
+            info.resource_address == auth_badge.resource_address() && info.local_id.as_ref() == Some(auth_badge.local_id())
+        }
+    }
+}
@@ -0,0 +1,457 @@
+//!
+//! `am-manifest` renders ready-to-sign manifests for every `AccessManager` operation.
+//!
+//! ```text
+//! am-manifest <operation> --network <simulator|stokenet|mainnet> --component <address> [--account <address>]
+//!     [--badge <resource address> --badge-id <local id>] [operation options] [--lock-fee <xrd>] [--compiled <file>]
+//! ```
+//!
+//! `--account`, `--badge` and `--badge-id` describe the badge the manifest authorizes with (owner badge or key badge).
+//! Only the operations that need a badge ask for them: views and `execute_validator_proposal` take none, and without
+//! `--account` no fee is locked, leaving that to the signing wallet.
+//! The manifest is printed as text, or written SBOR encoded to `--compiled <file>`.
+//! Run `am-manifest help` for the operations and their options.
+//!
+//! `am-manifest decode` inspects badge non-fungible data offline instead of building a manifest:
+//!
+//! ```text
+//! am-manifest decode --network <simulator|stokenet|mainnet> (--hex <sbor hex> | --json <programmatic json or file>)
+//! ```
+//!
+use accessmanager::access_manager::custom_types::{KeyBadgePermission, KeyTransferPolicy, RateLimit, RateLimitWindow, ValidatorOperation};
+use accessmanager::client::decoder::{decode_badge_hex, decode_badge_programmatic_json, describe_badge};
+use accessmanager::client::manifest_builder::{AccessManagerManifestBuilder, BadgeHolder};
+use radix_transactions::manifest::decompile;
+use radix_transactions::prelude::*;
+use scrypto::prelude::*;
+use std::collections::HashMap;
+use std::process::ExitCode;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+const USAGE: &str = "usage: am-manifest <operation> --network <simulator|stokenet|mainnet> --component <address> [--account <address>] [--badge <resource address> --badge-id <local id>] [options]
+
+       am-manifest decode --network <simulator|stokenet|mainnet> (--hex <sbor hex> | --json <programmatic json or file>)
+
+decode prints the manager, auth badge and permissions of an owner or access key badge and flags dangerous permissions.
+--hex takes the Gateway `raw_hex` of the non-fungible data, --json its `programmatic_json` (inline or a file path).
+
+common options:
+    --account <address>                  the account holding the badge, also pays the fee
+    --badge <resource> --badge-id <id>   the owner or key badge, required by every operation marked *
+    --lock-fee <xrd>                     fee locked from --account (default 10), no fee is locked without --account
+    --compiled <file>                    write the compiled manifest to <file> instead of printing it
+
+operations (operation specific options in brackets, * authorizes with --account, --badge and --badge-id):
+  * deposit_auth_badge                   --auth-badge <resource> --auth-badge-id <local id>
+  * withdraw_auth_badge
+  * create_super_access_key_badge        --include-validator-permissions <bool> --recipient <account>
+  * set_payout_account                   [--payout-account <account>]
+  * register_signature_delegate          --signer <secp256k1 or ed25519 public key hex> --permissions <Permission,Permission,...>
+  * remove_signature_delegate            --signer <secp256k1 or ed25519 public key hex>
+  * set_rate_limit                       --permission <Permission> [--max-calls <count> (--epochs <count> | --seconds <count>)]
+  * set_key_rate_limit                   --key-id <local id> --permission <Permission> [--max-calls <count> (--epochs <count> | --seconds <count>)]
+  * set_delegate_rate_limit              --signer <secp256k1 or ed25519 public key hex> --permission <Permission> [--max-calls <count> (--epochs <count> | --seconds <count>)]
+  * create_basic_key_badge               --include-validator-permissions <bool> --recipient <account>
+  * create_custom_access_key_badge       --permissions <Permission,Permission,...> --recipient <account> [--transfer-policy <soulbound|transferable>] [--bound-account <account>]
+  * transfer_key_badge                   --vault <internal address> --key-id <local id> --destination <account>
+  * recall_key_badge                     --vault <internal address>
+  * recall_key_badges_by_id              --vault <internal address> --key-ids <local id,local id,...>
+  * recall_and_burn_key_badges           --keys <vault=local id,local id,...;vault=local id,...>
+  * renounce_permissions                 --permissions <Permission,Permission,...>
+  * renounce_key                         --vault <internal address>
+  * recall_and_burn_key_badge            --key-badge <resource> --vault <internal address>
+  * create_auth_badge_proof
+  * deposit_to_treasury                  --resource <resource> --amount <decimal>
+  * withdraw_from_treasury               --resource <resource> --amount <decimal>
+  * validator_register
+  * validator_unregister
+  * validator_stake_as_owner             --amount <decimal>
+  * validator_stake_as_owner_to_payout   --amount <decimal>
+  * validator_stake_from_treasury        --amount <decimal>
+  * validator_update_key                 --key <secp256k1 public key hex>
+  * set_validator_operation_delay        --method <validator_update_key|validator_unregister> [--delay-seconds <seconds>]
+  * register_guardian                    --guardian <non-fungible global id>
+  * remove_guardian                      --guardian <non-fungible global id>
+  * propose_validator_update_key         --key <secp256k1 public key hex>
+  * propose_validator_unregister
+    execute_validator_proposal           --proposal-id <id>
+  * veto_validator_proposal              --proposal-id <id>
+  * validator_update_fee                 --fee <decimal>
+  * validator_update_accept_delegated_stake --accept <bool>
+  * validator_signal_protocol_update_readiness --vote <string>
+  * validator_lock_owner_stake_units     --stake-unit <resource> --amount <decimal>
+  * validator_lock_owner_stake_units_from_treasury --amount <decimal>
+  * validator_start_unlock_owner_stake_units --amount <decimal>
+  * validator_finish_unlock_owner_stake_units
+  * validator_finish_unlock_owner_stake_units_to_payout
+  * validator_compound_owner_stake
+  * validator_set_metadata               --key <metadata key> --value <string>
+  * validator_lock_metadata              --key <metadata key>
+  * validator_set_metadata_role          --role <role name> --rule <allow_all|deny_all|resource|resource:local id>
+  * create_session_key                   --session-key-badge <resource> --permissions <Permission,Permission,...>
+                                         mints a session key into the `session_key` bucket and burns it at the end,
+                                         insert the instructions that use it before the burn
+    get_auth_badge_info
+    get_resource_addresses
+    get_validator_address
+    get_config
+    get_signature_delegates
+    get_rate_limits
+    get_key_rate_limit                   --key-id <local id> --permission <Permission>
+    get_delegate_rate_limit              --signer <secp256k1 or ed25519 public key hex> --permission <Permission>
+    get_validator_operation_delays
+    get_guardians
+    get_validator_proposals
+    get_key_burned_at                    --key-id <local id>
+  * describe_key_permissions
+  * can_call                             --method <method name>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let (operation, options) = match args.split_first() {
+        Some((operation, _)) if operation == "help" || operation == "--help" => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        Some((operation, rest)) => (operation.as_str(), Options::parse(rest)?),
+        None => return Err(format!("missing operation\n\n{}", USAGE)),
+    };
+
+    let network = options.network()?;
+    if operation == "decode" {
+        return decode(&options, &network);
+    }
+    let manifest = build_manifest(operation, &options, &network)?;
+
+    match options.get("compiled") {
+        Some(path) => {
+            let compiled = manifest_encode(&manifest).map_err(|error| format!("cannot encode manifest: {:?}", error))?;
+            std::fs::write(path, compiled).map_err(|error| format!("cannot write {}: {}", path, error))
+        }
+        None => {
+            let text = decompile(&manifest.instructions, &network).map_err(|error| format!("cannot render manifest: {:?}", error))?;
+            println!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+fn decode(options: &Options, network: &NetworkDefinition) -> Result<(), String> {
+    let badge = match (options.get("hex"), options.get("json")) {
+        (Some(hex), None) => decode_badge_hex(hex)?,
+        (None, Some(json)) => {
+            // accept either the JSON itself or a path to a file holding it
+            let json = match std::fs::read_to_string(json) {
+                Ok(contents) => contents,
+                Err(_) => json.clone(),
+            };
+            decode_badge_programmatic_json(&json, network)?
+        }
+        _ => return Err("decode takes exactly one of --hex or --json".to_string()),
+    };
+    println!("{}", describe_badge(&badge, network));
+    Ok(())
+}
+
+fn build_manifest(operation: &str, options: &Options, network: &NetworkDefinition) -> Result<TransactionManifestV1, String> {
+    let component = options.component_address("component", network)?;
+    // only the operations that authorize with a badge ask for it
+    let holder = || options.holder(network);
+    let lock_fee = match options.get("lock-fee") {
+        Some(_) => options.decimal("lock-fee")?,
+        None => Decimal::from(10),
+    };
+    // without --account the fee is left to the wallet that signs the manifest
+    let builder = match options.get("account") {
+        Some(_) => ManifestBuilder::new().lock_fee(options.component_address("account", network)?, lock_fee),
+        None => ManifestBuilder::new(),
+    };
+
+    let builder = match operation {
+        "deposit_auth_badge" => builder.am_deposit_auth_badge(component, &holder()?, options.resource_address("auth-badge", network)?, options.local_id("auth-badge-id")?),
+        "withdraw_auth_badge" => builder.am_withdraw_auth_badge(component, &holder()?),
+        "create_super_access_key_badge" => builder.am_create_super_key(component, options.bool("include-validator-permissions")?, &holder()?, options.component_address("recipient", network)?),
+        "set_payout_account" => {
+            let payout_account = match options.get("payout-account") {
+                Some(_) => Some(options.component_address("payout-account", network)?),
+                None => None,
+            };
+            builder.am_set_payout_account(component, payout_account, &holder()?)
+        }
+        "register_signature_delegate" => builder.am_register_signature_delegate(component, options.signer("signer")?, &options.permissions("permissions")?, &holder()?),
+        "remove_signature_delegate" => builder.am_remove_signature_delegate(component, options.signer("signer")?, &holder()?),
+        "set_rate_limit" => builder.am_set_rate_limit(component, &options.permission("permission")?, options.rate_limit()?, &holder()?),
+        "set_key_rate_limit" => builder.am_set_key_rate_limit(component, options.local_id("key-id")?, &options.permission("permission")?, options.rate_limit()?, &holder()?),
+        "set_delegate_rate_limit" => builder.am_set_delegate_rate_limit(component, options.signer("signer")?, &options.permission("permission")?, options.rate_limit()?, &holder()?),
+        "create_basic_key_badge" => builder.am_create_basic_key(component, options.bool("include-validator-permissions")?, &holder()?, options.component_address("recipient", network)?),
+        "create_custom_access_key_badge" => match (options.get("transfer-policy"), options.get("bound-account")) {
+            (_, Some(_)) => {
+                let transfer_policy = match options.get("transfer-policy") {
+                    Some(_) => options.transfer_policy("transfer-policy")?,
+                    None => KeyTransferPolicy::Soulbound,
+                };
+                builder.am_create_bound_key(component, &options.permissions("permissions")?, transfer_policy, options.component_address("bound-account", network)?, &holder()?, options.component_address("recipient", network)?)
+            }
+            (Some(_), None) => builder.am_create_custom_key_with_policy(component, &options.permissions("permissions")?, options.transfer_policy("transfer-policy")?, &holder()?, options.component_address("recipient", network)?),
+            (None, None) => builder.am_create_custom_key(component, &options.permissions("permissions")?, &holder()?, options.component_address("recipient", network)?),
+        },
+        "transfer_key_badge" => builder.am_transfer_key(component, options.internal_address("vault", network)?, options.local_id("key-id")?, options.component_address("destination", network)?, &holder()?),
+        "renounce_permissions" => builder.am_renounce_permissions(component, &options.permissions("permissions")?, &holder()?),
+        "renounce_key" => builder.am_renounce_key(component, options.internal_address("vault", network)?, &holder()?),
+        "recall_key_badge" => builder.am_recall_key(component, options.internal_address("vault", network)?, &holder()?),
+        "recall_and_burn_key_badges" => builder.am_recall_and_burn_keys(component, &options.vault_keys("keys", network)?, &holder()?),
+        "recall_key_badges_by_id" => builder.am_recall_keys_by_id(component, options.internal_address("vault", network)?, &options.local_ids("key-ids")?, &holder()?),
+        "recall_and_burn_key_badge" => builder.am_recall_and_burn_key(component, options.resource_address("key-badge", network)?, options.internal_address("vault", network)?, &holder()?),
+        "create_auth_badge_proof" => builder.am_create_auth_badge_proof(component, &holder()?),
+        "deposit_to_treasury" => builder.am_deposit_to_treasury(component, options.resource_address("resource", network)?, options.decimal("amount")?, &holder()?),
+        "withdraw_from_treasury" => builder.am_withdraw_from_treasury(component, options.resource_address("resource", network)?, options.decimal("amount")?, &holder()?),
+        "validator_register" => builder.am_validator_register(component, &holder()?),
+        "validator_unregister" => builder.am_validator_unregister(component, &holder()?),
+        "validator_stake_as_owner" => builder.am_validator_stake_as_owner(component, options.decimal("amount")?, &holder()?),
+        "validator_stake_as_owner_to_payout" => builder.am_validator_stake_as_owner_to_payout(component, options.decimal("amount")?, &holder()?),
+        "validator_stake_from_treasury" => builder.am_validator_stake_from_treasury(component, options.decimal("amount")?, &holder()?),
+        "validator_update_key" => builder.am_validator_update_key(component, options.public_key("key")?, &holder()?),
+        "set_validator_operation_delay" => {
+            let delay_seconds = options.get("delay-seconds").map(|_| options.integer("delay-seconds")).transpose()?;
+            builder.am_set_validator_operation_delay(component, &options.string("method")?, delay_seconds, &holder()?)
+        }
+        "register_guardian" => builder.am_register_guardian(component, options.global_id("guardian", network)?, &holder()?),
+        "remove_guardian" => builder.am_remove_guardian(component, options.global_id("guardian", network)?, &holder()?),
+        "propose_validator_update_key" => builder.am_propose_validator_operation(component, ValidatorOperation::UpdateKey(options.public_key("key")?), &holder()?),
+        "propose_validator_unregister" => builder.am_propose_validator_operation(component, ValidatorOperation::Unregister, &holder()?),
+        "execute_validator_proposal" => builder.am_execute_validator_proposal(component, options.unsigned("proposal-id")?),
+        "veto_validator_proposal" => builder.am_veto_validator_proposal(component, options.unsigned("proposal-id")?, &holder()?),
+        "validator_update_fee" => builder.am_validator_update_fee(component, options.decimal("fee")?, &holder()?),
+        "validator_update_accept_delegated_stake" => builder.am_validator_update_accept_delegated_stake(component, options.bool("accept")?, &holder()?),
+        "validator_signal_protocol_update_readiness" => builder.am_validator_signal_protocol_update_readiness(component, options.string("vote")?, &holder()?),
+        "validator_lock_owner_stake_units" => builder.am_validator_lock_owner_stake_units(component, options.resource_address("stake-unit", network)?, options.decimal("amount")?, &holder()?),
+        "validator_lock_owner_stake_units_from_treasury" => builder.am_validator_lock_owner_stake_units_from_treasury(component, options.decimal("amount")?, &holder()?),
+        "validator_start_unlock_owner_stake_units" => builder.am_validator_start_unlock_owner_stake_units(component, options.decimal("amount")?, &holder()?),
+        "validator_finish_unlock_owner_stake_units" => builder.am_validator_finish_unlock_owner_stake_units(component, &holder()?),
+        "validator_finish_unlock_owner_stake_units_to_payout" => builder.am_validator_finish_unlock_owner_stake_units_to_payout(component, &holder()?),
+        "validator_compound_owner_stake" => builder.am_validator_compound_owner_stake(component, &holder()?),
+        "validator_set_metadata" => builder.am_validator_set_metadata(component, options.string("key")?, options.string("value")?, &holder()?),
+        "validator_lock_metadata" => builder.am_validator_lock_metadata(component, options.string("key")?, &holder()?),
+        "validator_set_metadata_role" => builder.am_validator_set_metadata_role(component, options.string("role")?, options.access_rule("rule", network)?, &holder()?),
+        "create_session_key" => {
+            let session_key_badge = options.resource_address("session-key-badge", network)?;
+            builder
+                .am_create_session_key(component, session_key_badge, &options.permissions("permissions")?, &holder()?, "session_key")
+                .am_burn_session_key(component, "session_key", &holder()?)
+        }
+        "get_auth_badge_info" => builder.am_get_auth_badge_info(component),
+        "get_resource_addresses" => builder.am_get_resource_addresses(component),
+        "get_validator_address" => builder.am_get_validator_address(component),
+        "get_config" => builder.am_get_config(component),
+        "get_signature_delegates" => builder.am_get_signature_delegates(component),
+        "get_rate_limits" => builder.am_get_rate_limits(component),
+        "get_key_rate_limit" => builder.am_get_key_rate_limit(component, options.local_id("key-id")?, &options.permission("permission")?),
+        "get_delegate_rate_limit" => builder.am_get_delegate_rate_limit(component, options.signer("signer")?, &options.permission("permission")?),
+        "get_validator_operation_delays" => builder.am_get_validator_operation_delays(component),
+        "get_guardians" => builder.am_get_guardians(component),
+        "get_validator_proposals" => builder.am_get_validator_proposals(component),
+        "get_key_burned_at" => builder.am_get_key_burned_at(component, options.local_id("key-id")?),
+        "describe_key_permissions" => builder.am_describe_key_permissions(component, &holder()?),
+        "can_call" => builder.am_can_call(component, &options.string("method")?, &holder()?),
+        _ => return Err(format!("unknown operation `{}`, run `am-manifest help` for the list of operations", operation)),
+    };
+    Ok(builder.build())
+}
+
+// `--name value` pairs following the operation
+struct Options(HashMap<String, String>);
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg.strip_prefix("--").ok_or_else(|| format!("unexpected argument `{}`", arg))?;
+            let value = args.next().ok_or_else(|| format!("missing value for --{}", name))?;
+            options.insert(name.to_string(), value.clone());
+        }
+        Ok(Self(options))
+    }
+
+    fn get(&self, name: &str) -> Option<&String> {
+        self.0.get(name)
+    }
+
+    // `--account`, `--badge` and `--badge-id`, the badge the manifest authorizes with
+    fn holder(&self, network: &NetworkDefinition) -> Result<BadgeHolder, String> {
+        Ok(BadgeHolder {
+            account: self.component_address("account", network)?,
+            badge: self.resource_address("badge", network)?,
+            id: self.local_id("badge-id")?,
+        })
+    }
+
+    fn string(&self, name: &str) -> Result<String, String> {
+        self.get(name).cloned().ok_or_else(|| format!("missing --{}", name))
+    }
+
+    fn network(&self) -> Result<NetworkDefinition, String> {
+        match self.string("network")?.as_str() {
+            "simulator" => Ok(NetworkDefinition::simulator()),
+            "stokenet" => Ok(NetworkDefinition::stokenet()),
+            "mainnet" => Ok(NetworkDefinition::mainnet()),
+            other => Err(format!("unknown network `{}`, expected simulator, stokenet or mainnet", other)),
+        }
+    }
+
+    fn component_address(&self, name: &str, network: &NetworkDefinition) -> Result<ComponentAddress, String> {
+        ComponentAddress::try_from_bech32(&AddressBech32Decoder::new(network), &self.string(name)?)
+            .ok_or_else(|| format!("--{} is not a component address on {}", name, network.logical_name))
+    }
+
+    fn resource_address(&self, name: &str, network: &NetworkDefinition) -> Result<ResourceAddress, String> {
+        ResourceAddress::try_from_bech32(&AddressBech32Decoder::new(network), &self.string(name)?)
+            .ok_or_else(|| format!("--{} is not a resource address on {}", name, network.logical_name))
+    }
+
+    fn internal_address(&self, name: &str, network: &NetworkDefinition) -> Result<InternalAddress, String> {
+        InternalAddress::try_from_bech32(&AddressBech32Decoder::new(network), &self.string(name)?)
+            .ok_or_else(|| format!("--{} is not an internal address on {}", name, network.logical_name))
+    }
+
+    fn local_id(&self, name: &str) -> Result<NonFungibleLocalId, String> {
+        NonFungibleLocalId::from_str(&self.string(name)?).map_err(|error| format!("--{} is not a non-fungible local id: {:?}", name, error))
+    }
+
+    // comma separated non-fungible local ids
+    fn local_ids(&self, name: &str) -> Result<Vec<NonFungibleLocalId>, String> {
+        self.string(name)?
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| NonFungibleLocalId::from_str(id).map_err(|error| format!("--{} holds an invalid non-fungible local id `{}`: {:?}", name, id, error)))
+            .collect()
+    }
+
+    // `;` separated `vault=local id,local id,...` groups
+    fn vault_keys(&self, name: &str, network: &NetworkDefinition) -> Result<Vec<(InternalAddress, Vec<NonFungibleLocalId>)>, String> {
+        let decoder = AddressBech32Decoder::new(network);
+        self.string(name)?
+            .split(';')
+            .map(str::trim)
+            .filter(|group| !group.is_empty())
+            .map(|group| {
+                let (vault, ids) = group.split_once('=').ok_or_else(|| format!("--{} group `{}` is not of the form vault=local id,...", name, group))?;
+                let vault = InternalAddress::try_from_bech32(&decoder, vault.trim())
+                    .ok_or_else(|| format!("--{} holds `{}`, which is not an internal address on {}", name, vault, network.logical_name))?;
+                let ids = ids
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(|id| NonFungibleLocalId::from_str(id).map_err(|error| format!("--{} holds an invalid non-fungible local id `{}`: {:?}", name, id, error)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((vault, ids))
+            })
+            .collect()
+    }
+
+    fn integer(&self, name: &str) -> Result<i64, String> {
+        i64::from_str(&self.string(name)?).map_err(|_| format!("--{} is not an integer", name))
+    }
+
+    fn unsigned(&self, name: &str) -> Result<u64, String> {
+        u64::from_str(&self.string(name)?).map_err(|_| format!("--{} is not a non-negative integer", name))
+    }
+
+    // the canonical `<resource address>:<local id>` form
+    fn global_id(&self, name: &str, network: &NetworkDefinition) -> Result<NonFungibleGlobalId, String> {
+        NonFungibleGlobalId::try_from_canonical_string(&AddressBech32Decoder::new(network), &self.string(name)?)
+            .map_err(|error| format!("--{} is not a non-fungible global id: {:?}", name, error))
+    }
+
+    fn decimal(&self, name: &str) -> Result<Decimal, String> {
+        Decimal::from_str(&self.string(name)?).map_err(|error| format!("--{} is not a decimal: {:?}", name, error))
+    }
+
+    fn bool(&self, name: &str) -> Result<bool, String> {
+        bool::from_str(&self.string(name)?).map_err(|_| format!("--{} must be true or false", name))
+    }
+
+    fn public_key(&self, name: &str) -> Result<Secp256k1PublicKey, String> {
+        Secp256k1PublicKey::from_str(&self.string(name)?).map_err(|error| format!("--{} is not a secp256k1 public key: {:?}", name, error))
+    }
+
+    // the virtual signature badge of a secp256k1 (33 bytes) or ed25519 (32 bytes) public key
+    fn signer(&self, name: &str) -> Result<NonFungibleGlobalId, String> {
+        let hex = self.string(name)?;
+        let public_key: PublicKey = match Secp256k1PublicKey::from_str(&hex) {
+            Ok(public_key) => public_key.into(),
+            Err(_) => Ed25519PublicKey::from_str(&hex)
+                .map_err(|error| format!("--{} is neither a secp256k1 nor an ed25519 public key: {:?}", name, error))?
+                .into(),
+        };
+        Ok(NonFungibleGlobalId::from_public_key(&public_key))
+    }
+
+    // `allow_all`, `deny_all`, a resource address or a `<resource address>:<local id>` badge
+    fn access_rule(&self, name: &str, network: &NetworkDefinition) -> Result<AccessRule, String> {
+        let rule = self.string(name)?;
+        match rule.as_str() {
+            "allow_all" => Ok(AccessRule::AllowAll),
+            "deny_all" => Ok(AccessRule::DenyAll),
+            _ if rule.contains(':') => Ok(rule!(require(self.global_id(name, network)?))),
+            _ => Ok(rule!(require(self.resource_address(name, network)?))),
+        }
+    }
+
+    fn transfer_policy(&self, name: &str) -> Result<KeyTransferPolicy, String> {
+        match self.string(name)?.as_str() {
+            "soulbound" => Ok(KeyTransferPolicy::Soulbound),
+            "transferable" => Ok(KeyTransferPolicy::Transferable),
+            other => Err(format!("unknown transfer policy `{}`, expected soulbound or transferable", other)),
+        }
+    }
+
+    fn permission(&self, name: &str) -> Result<KeyBadgePermission, String> {
+        match self.permissions(name)?.as_slice() {
+            [permission] => Ok(permission.clone()),
+            _ => Err(format!("--{} must name exactly one permission", name)),
+        }
+    }
+
+    // `--max-calls` per `--epochs` or `--seconds` window, no `--max-calls` removes the limit
+    fn rate_limit(&self) -> Result<Option<RateLimit>, String> {
+        if self.get("max-calls").is_none() {
+            return Ok(None);
+        }
+        let max_calls = u32::from_str(&self.string("max-calls")?).map_err(|_| "--max-calls is not a count".to_string())?;
+        let count = |name: &str| u64::from_str(&self.string(name)?).map_err(|_| format!("--{} is not a count", name));
+        let window = match (self.get("epochs"), self.get("seconds")) {
+            (Some(_), None) => RateLimitWindow::Epochs(count("epochs")?),
+            (None, Some(_)) => RateLimitWindow::Seconds(count("seconds")?),
+            _ => return Err("a rate limit needs exactly one of --epochs or --seconds".to_string()),
+        };
+        Ok(Some(RateLimit { max_calls, window }))
+    }
+
+    // comma separated permission names, each must be a `KeyBadgePermission` variant
+    fn permissions(&self, name: &str) -> Result<Vec<KeyBadgePermission>, String> {
+        self.string(name)?
+            .split(',')
+            .map(str::trim)
+            .filter(|permission| !permission.is_empty())
+            .map(|permission| {
+                KeyBadgePermission::from_str(permission).map_err(|_| {
+                    let valid: Vec<String> = KeyBadgePermission::iter().map(|permission| permission.to_string()).collect();
+                    format!("unknown permission `{}`, valid permissions are: {}", permission, valid.join(", "))
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,145 @@
+use scrypto::prelude::*;
+use serde_json::Value;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use crate::access_manager::custom_types::{AccessKeyBadgeData, KeyBadgePermission, KeyTransferPolicy, OwnerBadgeData};
+
+///
+/// Non-fungible data of an Access Manager badge, decoded without a node.
+///
+pub enum DecodedBadge {
+    AccessKey(AccessKeyBadgeData),
+    Owner(OwnerBadgeData),
+}
+
+///
+/// Decodes SBOR encoded non-fungible data (e.g. the Gateway `raw_hex` field) into either badge data type.
+/// The two types have a different number of fields, so at most one of them decodes.
+///
+pub fn decode_badge_sbor(bytes: &[u8]) -> Result<DecodedBadge, String> {
+    if let Ok(data) = scrypto_decode::<AccessKeyBadgeData>(bytes) {
+        return Ok(DecodedBadge::AccessKey(data));
+    }
+    scrypto_decode::<OwnerBadgeData>(bytes)
+        .map(DecodedBadge::Owner)
+        .map_err(|error| format!("data is neither AccessKeyBadgeData nor OwnerBadgeData: {:?}", error))
+}
+
+pub fn decode_badge_hex(hex: &str) -> Result<DecodedBadge, String> {
+    let bytes = hex::decode(hex.trim().trim_start_matches("0x")).map_err(|error| format!("invalid hex: {}", error))?;
+    decode_badge_sbor(&bytes)
+}
+
+///
+/// Decodes the Gateway `programmatic_json` of the non-fungible data.
+/// Permissions are matched by `variant_name` when the Gateway includes it, otherwise by `variant_id`, which is the declaration index in `KeyBadgePermission`.
+///
+pub fn decode_badge_programmatic_json(json: &str, network: &NetworkDefinition) -> Result<DecodedBadge, String> {
+    let value: Value = serde_json::from_str(json).map_err(|error| format!("invalid JSON: {}", error))?;
+    let fields = value["fields"].as_array().ok_or("expected a Tuple with `fields`")?;
+    let decoder = AddressBech32Decoder::new(network);
+    let address_field = |index: usize| -> Result<&str, String> {
+        fields.get(index).and_then(|field| field["value"].as_str()).ok_or(format!("field {} is not an address", index))
+    };
+
+    let manager_component_address = ComponentAddress::try_from_bech32(&decoder, address_field(0)?)
+        .ok_or(format!("field 0 is not a component address on {}", network.logical_name))?;
+    let auth_badge_address = ResourceAddress::try_from_bech32(&decoder, address_field(1)?)
+        .ok_or(format!("field 1 is not a resource address on {}", network.logical_name))?;
+
+    match fields.len() {
+        2 => Ok(DecodedBadge::Owner(OwnerBadgeData { manager_component_address, auth_badge_address })),
+        5 => {
+            let elements = fields[2]["elements"].as_array().ok_or("field 2 is not an Array of permissions")?;
+            let permissions = elements.iter().map(permission_from_json).collect::<Result<Vec<_>, _>>()?;
+            let transfer_policy = match variant_from_json(&fields[3])? {
+                (Some("Soulbound"), _) | (None, 0) => KeyTransferPolicy::Soulbound,
+                (Some("Transferable"), _) | (None, 1) => KeyTransferPolicy::Transferable,
+                (name, id) => return Err(format!("unknown transfer policy {}", name.map(str::to_string).unwrap_or(id.to_string()))),
+            };
+            let bound_account = match variant_from_json(&fields[4])? {
+                (Some("None"), _) | (None, 0) => None,
+                (Some("Some"), _) | (None, 1) => {
+                    let account = fields[4]["fields"][0]["value"].as_str().ok_or("field 4 holds no address")?;
+                    Some(ComponentAddress::try_from_bech32(&decoder, account).ok_or(format!("field 4 is not a component address on {}", network.logical_name))?)
+                }
+                (name, id) => return Err(format!("unknown bound account variant {}", name.map(str::to_string).unwrap_or(id.to_string()))),
+            };
+            Ok(DecodedBadge::AccessKey(AccessKeyBadgeData { manager_component_address, auth_badge_address, permissions, transfer_policy, bound_account }))
+        }
+        count => Err(format!("expected 2 (owner badge) or 5 (access key badge) fields, found {}", count)),
+    }
+}
+
+fn permission_from_json(element: &Value) -> Result<KeyBadgePermission, String> {
+    match variant_from_json(element)? {
+        (Some(name), _) => KeyBadgePermission::from_str(name).map_err(|_| format!("unknown permission `{}`", name)),
+        (None, variant_id) => KeyBadgePermission::iter()
+            .nth(variant_id as usize)
+            .ok_or(format!("unknown permission variant id {}", variant_id)),
+    }
+}
+
+// the `variant_name` of an Enum when the Gateway includes it, and its `variant_id`
+fn variant_from_json(element: &Value) -> Result<(Option<&str>, u64), String> {
+    // the Gateway renders variant ids either as numbers or as strings
+    let variant_id = match &element["variant_id"] {
+        Value::Number(id) => id.as_u64(),
+        Value::String(id) => id.parse().ok(),
+        _ => None,
+    };
+    match (element["variant_name"].as_str(), variant_id) {
+        (None, None) => Err("enum has neither a variant_name nor a variant_id".to_string()),
+        (name, id) => Ok((name, id.unwrap_or_default())),
+    }
+}
+
+///
+/// Why holding `permission` deserves attention, `None` for routine permissions.
+///
+pub fn permission_warning(permission: &KeyBadgePermission) -> Option<&'static str> {
+    match permission {
+        KeyBadgePermission::CreateNativeProof => Some("the holder gets a native proof of the auth badge and can use it for anything outside the component"),
+        KeyBadgePermission::CreateAccessKey => Some("the holder can mint new access keys"),
+        KeyBadgePermission::RecallAccessKey => Some("the holder can recall other access keys"),
+        KeyBadgePermission::Treasury_Withdraw => Some("the holder can withdraw the component treasury"),
+        KeyBadgePermission::Validator_StakeAsOwner | KeyBadgePermission::Validator_FinishUnlockOwnerStakeUnits => Some("the holder receives new or unlocked owner stake units"),
+        KeyBadgePermission::Validator_Unregister | KeyBadgePermission::Validator_UpdateKey => Some("the holder can take the validator out of the active set"),
+        KeyBadgePermission::Validator_SetMetadataRole => Some("the holder can change who may edit the validator metadata"),
+        _ => None,
+    }
+}
+
+///
+/// Human-readable report of a decoded badge, addresses rendered for `network`.
+///
+pub fn describe_badge(badge: &DecodedBadge, network: &NetworkDefinition) -> String {
+    let encoder = AddressBech32Encoder::new(network);
+    let (kind, manager, auth_badge) = match badge {
+        DecodedBadge::AccessKey(data) => ("Access Manager Access Key Badge", data.manager_component_address, data.auth_badge_address),
+        DecodedBadge::Owner(data) => ("Access Manager Owner Badge", data.manager_component_address, data.auth_badge_address),
+    };
+    let mut lines = vec![
+        kind.to_string(),
+        format!("  manager component: {}", manager.display(&encoder)),
+        format!("  auth badge:        {}", auth_badge.display(&encoder)),
+    ];
+    match badge {
+        DecodedBadge::Owner(_) => lines.push("  permissions:       all (owner)".to_string()),
+        DecodedBadge::AccessKey(data) => {
+            lines.push(format!("  transfer policy:   {:?}", data.transfer_policy));
+            match data.bound_account {
+                Some(account) => lines.push(format!("  bound account:     {}", account.display(&encoder))),
+                None => lines.push("  bound account:     none, usable from any account".to_string()),
+            }
+            lines.push("  permissions:".to_string());
+            for permission in &data.permissions {
+                match permission_warning(permission) {
+                    Some(warning) => lines.push(format!("    - {} [DANGEROUS: {}]", permission, warning)),
+                    None => lines.push(format!("    - {}", permission)),
+                }
+            }
+        }
+    }
+    lines.join("\n")
+}
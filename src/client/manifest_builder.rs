@@ -0,0 +1,387 @@
+use scrypto::prelude::*;
+use radix_transactions::prelude::*;
+use crate::access_manager::custom_types::{KeyBadgePermission, KeyTransferPolicy, RateLimit, ValidatorOperation};
+
+///
+/// The badge a manifest authorizes with: the Access Manager owner badge or an access key badge, held in `account`.
+/// 
+#[derive(Debug, Clone)]
+pub struct BadgeHolder {
+    pub account: ComponentAddress,
+    pub badge: ResourceAddress,
+    pub id: NonFungibleLocalId,
+}
+
+///
+/// Typed `ManifestBuilder` helpers for the `AccessManager` blueprint.
+/// Every helper creates the proof it needs from `proof_of.account`, encodes the arguments, and deposits anything the
+/// method returns (key badges, recalled badges, stake units, XRD) into the given account, so a manifest is just a chain of calls.
+/// Key badges can only be deposited while an owner or key badge is in the auth zone, so the helpers re-create that proof before depositing.
+/// Signature delegates have no badge to prove: they call the methods directly with `None::<ManifestProof>` as the proof and sign the transaction.
+/// 
+pub trait AccessManagerManifestBuilder: Sized {
+    // owner methods, the owner badge is put in the auth zone
+    fn am_deposit_auth_badge(self, component: ComponentAddress, owner: &BadgeHolder, auth_badge: ResourceAddress, auth_badge_id: NonFungibleLocalId) -> Self;
+    fn am_withdraw_auth_badge(self, component: ComponentAddress, owner: &BadgeHolder) -> Self;
+    fn am_create_super_key(self, component: ComponentAddress, include_validator_permissions: bool, owner: &BadgeHolder, recipient: ComponentAddress) -> Self;
+    fn am_set_payout_account(self, component: ComponentAddress, payout_account: Option<ComponentAddress>, owner: &BadgeHolder) -> Self;
+    fn am_register_signature_delegate(self, component: ComponentAddress, delegate: NonFungibleGlobalId, permissions: &[KeyBadgePermission], owner: &BadgeHolder) -> Self;
+    fn am_remove_signature_delegate(self, component: ComponentAddress, delegate: NonFungibleGlobalId, owner: &BadgeHolder) -> Self;
+    // `None` lifts the limit, or for a key or delegate falls back to the component-wide limit
+    fn am_set_rate_limit(self, component: ComponentAddress, permission: &KeyBadgePermission, rate_limit: Option<RateLimit>, owner: &BadgeHolder) -> Self;
+    fn am_set_key_rate_limit(self, component: ComponentAddress, key_id: NonFungibleLocalId, permission: &KeyBadgePermission, rate_limit: Option<RateLimit>, owner: &BadgeHolder) -> Self;
+    fn am_set_delegate_rate_limit(self, component: ComponentAddress, delegate: NonFungibleGlobalId, permission: &KeyBadgePermission, rate_limit: Option<RateLimit>, owner: &BadgeHolder) -> Self;
+    fn am_set_validator_operation_delay(self, component: ComponentAddress, method_name: &str, delay_seconds: Option<i64>, owner: &BadgeHolder) -> Self;
+    fn am_register_guardian(self, component: ComponentAddress, guardian: NonFungibleGlobalId, owner: &BadgeHolder) -> Self;
+    fn am_remove_guardian(self, component: ComponentAddress, guardian: NonFungibleGlobalId, owner: &BadgeHolder) -> Self;
+
+    // factory methods, `auth_badge` is withdrawn from `account` and the new owner badge deposited there
+    fn am_factory_new_manager(self, factory: ComponentAddress, account: ComponentAddress, auth_badge: ResourceAddress, auth_badge_id: NonFungibleLocalId, dapp_definition: ComponentAddress) -> Self;
+
+    // key methods
+    fn am_create_basic_key(self, component: ComponentAddress, include_validator_permissions: bool, proof_of: &BadgeHolder, recipient: ComponentAddress) -> Self;
+    fn am_create_custom_key(self, component: ComponentAddress, permissions: &[KeyBadgePermission], proof_of: &BadgeHolder, recipient: ComponentAddress) -> Self;
+    fn am_create_custom_key_with_policy(self, component: ComponentAddress, permissions: &[KeyBadgePermission], transfer_policy: KeyTransferPolicy, proof_of: &BadgeHolder, recipient: ComponentAddress) -> Self;
+    // the key only works when presented together with a proof of controlling `bound_account`, usually the recipient
+    fn am_create_bound_key(self, component: ComponentAddress, permissions: &[KeyBadgePermission], transfer_policy: KeyTransferPolicy, bound_account: ComponentAddress, proof_of: &BadgeHolder, recipient: ComponentAddress) -> Self;
+    // `proof_of` is the key itself or the owner badge, the manifest does not touch the key afterwards since it leaves the vault
+    fn am_transfer_key(self, component: ComponentAddress, key_badge_vault: InternalAddress, key_id: NonFungibleLocalId, destination: ComponentAddress, proof_of: &BadgeHolder) -> Self;
+    fn am_recall_key(self, component: ComponentAddress, key_badge_vault: InternalAddress, proof_of: &BadgeHolder) -> Self;
+    fn am_recall_keys_by_id(self, component: ComponentAddress, key_badge_vault: InternalAddress, key_ids: &[NonFungibleLocalId], proof_of: &BadgeHolder) -> Self;
+    fn am_recall_and_burn_key(self, component: ComponentAddress, key_badge: ResourceAddress, key_badge_vault: InternalAddress, proof_of: &BadgeHolder) -> Self;
+    // recalls and burns the listed keys of every vault within the component, nothing reaches the worktop
+    fn am_recall_and_burn_keys(self, component: ComponentAddress, keys: &[(InternalAddress, Vec<NonFungibleLocalId>)], proof_of: &BadgeHolder) -> Self;
+    fn am_create_auth_badge_proof(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self;
+    // the session key is kept in the named bucket `session_bucket` so later instructions can create proofs of it,
+    // it must be passed to `am_burn_session_key` before the manifest ends
+    fn am_create_session_key(self, component: ComponentAddress, session_key_badge: ResourceAddress, permissions: &[KeyBadgePermission], proof_of: &BadgeHolder, session_bucket: &str) -> Self;
+    fn am_burn_session_key(self, component: ComponentAddress, session_bucket: &str, proof_of: &BadgeHolder) -> Self;
+    // `key` renounces permissions of, or resigns, its own key
+    fn am_renounce_permissions(self, component: ComponentAddress, permissions: &[KeyBadgePermission], key: &BadgeHolder) -> Self;
+    fn am_renounce_key(self, component: ComponentAddress, key_badge_vault: InternalAddress, key: &BadgeHolder) -> Self;
+
+    // treasury methods
+    fn am_deposit_to_treasury(self, component: ComponentAddress, resource: ResourceAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self;
+    fn am_withdraw_from_treasury(self, component: ComponentAddress, resource: ResourceAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self;
+
+    // view methods, meant for preview
+    fn am_get_auth_badge_info(self, component: ComponentAddress) -> Self;
+    fn am_get_resource_addresses(self, component: ComponentAddress) -> Self;
+    fn am_get_validator_address(self, component: ComponentAddress) -> Self;
+    fn am_get_config(self, component: ComponentAddress) -> Self;
+    fn am_get_signature_delegates(self, component: ComponentAddress) -> Self;
+    fn am_get_rate_limits(self, component: ComponentAddress) -> Self;
+    fn am_get_key_rate_limit(self, component: ComponentAddress, key_id: NonFungibleLocalId, permission: &KeyBadgePermission) -> Self;
+    fn am_get_delegate_rate_limit(self, component: ComponentAddress, delegate: NonFungibleGlobalId, permission: &KeyBadgePermission) -> Self;
+    fn am_get_validator_operation_delays(self, component: ComponentAddress) -> Self;
+    fn am_get_guardians(self, component: ComponentAddress) -> Self;
+    fn am_get_validator_proposals(self, component: ComponentAddress) -> Self;
+    fn am_get_key_burned_at(self, component: ComponentAddress, key_id: NonFungibleLocalId) -> Self;
+    fn am_describe_key_permissions(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self;
+    fn am_can_call(self, component: ComponentAddress, method_name: &str, proof_of: &BadgeHolder) -> Self;
+
+    // validator extension methods
+    fn am_validator_register(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_unregister(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_stake_as_owner(self, component: ComponentAddress, xrd_amount: Decimal, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_stake_as_owner_to_payout(self, component: ComponentAddress, xrd_amount: Decimal, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_stake_from_treasury(self, component: ComponentAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_update_key(self, component: ComponentAddress, key: Secp256k1PublicKey, proof_of: &BadgeHolder) -> Self;
+    fn am_propose_validator_operation(self, component: ComponentAddress, operation: ValidatorOperation, proof_of: &BadgeHolder) -> Self;
+    // needs no badge, the proposal was authorized when it was proposed
+    fn am_execute_validator_proposal(self, component: ComponentAddress, proposal_id: u64) -> Self;
+    // vetoes with the owner badge, guardians call `veto_validator_proposal` with `None` and their badge in the auth zone
+    fn am_veto_validator_proposal(self, component: ComponentAddress, proposal_id: u64, owner: &BadgeHolder) -> Self;
+    fn am_validator_update_fee(self, component: ComponentAddress, new_fee_factor: Decimal, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_update_accept_delegated_stake(self, component: ComponentAddress, accept_delegated_stake: bool, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_signal_protocol_update_readiness(self, component: ComponentAddress, vote: String, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_lock_owner_stake_units(self, component: ComponentAddress, stake_unit: ResourceAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_lock_owner_stake_units_from_treasury(self, component: ComponentAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_start_unlock_owner_stake_units(self, component: ComponentAddress, requested_stake_unit_amount: Decimal, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_finish_unlock_owner_stake_units(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_finish_unlock_owner_stake_units_to_payout(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_compound_owner_stake(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_set_metadata(self, component: ComponentAddress, key: String, value: String, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_lock_metadata(self, component: ComponentAddress, key: String, proof_of: &BadgeHolder) -> Self;
+    fn am_validator_set_metadata_role(self, component: ComponentAddress, role_name: String, rule: AccessRule, proof_of: &BadgeHolder) -> Self;
+}
+
+// private building blocks
+trait AccessManagerManifestSteps: Sized {
+    fn am_badge_in_auth_zone(self, holder: &BadgeHolder) -> Self;
+    fn am_call_with_proof(self, component: ComponentAddress, method: &str, proof_of: &BadgeHolder, args: impl FnOnce(ManifestProof) -> ManifestArgs) -> Self;
+    fn am_call_with_bucket_and_proof(self, component: ComponentAddress, method: &str, resource: ResourceAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self;
+    fn am_deposit_worktop(self, holder: &BadgeHolder, recipient: ComponentAddress) -> Self;
+}
+
+impl AccessManagerManifestSteps for ManifestBuilder {
+    fn am_badge_in_auth_zone(self, holder: &BadgeHolder) -> Self {
+        self.create_proof_from_account_of_non_fungibles(holder.account, holder.badge, [holder.id.clone()])
+    }
+    fn am_call_with_proof(self, component: ComponentAddress, method: &str, proof_of: &BadgeHolder, args: impl FnOnce(ManifestProof) -> ManifestArgs) -> Self {
+        let proof_name = self.generate_proof_name("access_manager_proof");
+        self.am_badge_in_auth_zone(proof_of)
+            .pop_from_auth_zone(&proof_name)
+            .call_method_with_name_lookup(component, method, |lookup| args(lookup.proof(&proof_name)))
+    }
+    fn am_call_with_bucket_and_proof(self, component: ComponentAddress, method: &str, resource: ResourceAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self {
+        let bucket_name = self.generate_bucket_name("access_manager_bucket");
+        let proof_name = self.generate_proof_name("access_manager_proof");
+        self.withdraw_from_account(proof_of.account, resource, amount)
+            .take_from_worktop(resource, amount, &bucket_name)
+            .am_badge_in_auth_zone(proof_of)
+            .pop_from_auth_zone(&proof_name)
+            .call_method_with_name_lookup(component, method, |lookup| (lookup.bucket(&bucket_name), Some(lookup.proof(&proof_name))))
+    }
+    fn am_deposit_worktop(self, holder: &BadgeHolder, recipient: ComponentAddress) -> Self {
+        self.am_badge_in_auth_zone(holder)
+            .try_deposit_entire_worktop_or_abort(recipient, None)
+    }
+}
+
+impl AccessManagerManifestBuilder for ManifestBuilder {
+    fn am_deposit_auth_badge(self, component: ComponentAddress, owner: &BadgeHolder, auth_badge: ResourceAddress, auth_badge_id: NonFungibleLocalId) -> Self {
+        let bucket_name = self.generate_bucket_name("auth_badge");
+        self.withdraw_non_fungibles_from_account(owner.account, auth_badge, [auth_badge_id.clone()])
+            .take_non_fungibles_from_worktop(auth_badge, [auth_badge_id], &bucket_name)
+            .am_badge_in_auth_zone(owner)
+            .call_method_with_name_lookup(component, "deposit_auth_badge", |lookup| (lookup.bucket(&bucket_name),))
+    }
+    fn am_withdraw_auth_badge(self, component: ComponentAddress, owner: &BadgeHolder) -> Self {
+        self.am_badge_in_auth_zone(owner)
+            .call_method(component, "withdraw_auth_badge", manifest_args!())
+            .try_deposit_entire_worktop_or_abort(owner.account, None)
+    }
+    fn am_create_super_key(self, component: ComponentAddress, include_validator_permissions: bool, owner: &BadgeHolder, recipient: ComponentAddress) -> Self {
+        self.am_badge_in_auth_zone(owner)
+            .call_method(component, "create_super_access_key_badge", manifest_args!(include_validator_permissions))
+            .try_deposit_entire_worktop_or_abort(recipient, None)
+    }
+    fn am_set_payout_account(self, component: ComponentAddress, payout_account: Option<ComponentAddress>, owner: &BadgeHolder) -> Self {
+        self.am_badge_in_auth_zone(owner)
+            .call_method(component, "set_payout_account", manifest_args!(payout_account))
+    }
+    fn am_register_signature_delegate(self, component: ComponentAddress, delegate: NonFungibleGlobalId, permissions: &[KeyBadgePermission], owner: &BadgeHolder) -> Self {
+        let permissions: Vec<String> = permissions.iter().map(|permission| permission.to_string()).collect();
+        self.am_badge_in_auth_zone(owner)
+            .call_method(component, "register_signature_delegate", manifest_args!(delegate, permissions))
+    }
+    fn am_remove_signature_delegate(self, component: ComponentAddress, delegate: NonFungibleGlobalId, owner: &BadgeHolder) -> Self {
+        self.am_badge_in_auth_zone(owner)
+            .call_method(component, "remove_signature_delegate", manifest_args!(delegate))
+    }
+    fn am_set_rate_limit(self, component: ComponentAddress, permission: &KeyBadgePermission, rate_limit: Option<RateLimit>, owner: &BadgeHolder) -> Self {
+        self.am_badge_in_auth_zone(owner)
+            .call_method(component, "set_rate_limit", manifest_args!(permission.to_string(), rate_limit))
+    }
+    fn am_set_key_rate_limit(self, component: ComponentAddress, key_id: NonFungibleLocalId, permission: &KeyBadgePermission, rate_limit: Option<RateLimit>, owner: &BadgeHolder) -> Self {
+        self.am_badge_in_auth_zone(owner)
+            .call_method(component, "set_key_rate_limit", manifest_args!(key_id, permission.to_string(), rate_limit))
+    }
+    fn am_set_delegate_rate_limit(self, component: ComponentAddress, delegate: NonFungibleGlobalId, permission: &KeyBadgePermission, rate_limit: Option<RateLimit>, owner: &BadgeHolder) -> Self {
+        self.am_badge_in_auth_zone(owner)
+            .call_method(component, "set_delegate_rate_limit", manifest_args!(delegate, permission.to_string(), rate_limit))
+    }
+    fn am_set_validator_operation_delay(self, component: ComponentAddress, method_name: &str, delay_seconds: Option<i64>, owner: &BadgeHolder) -> Self {
+        self.am_badge_in_auth_zone(owner)
+            .call_method(component, "set_validator_operation_delay", manifest_args!(method_name.to_string(), delay_seconds))
+    }
+    fn am_register_guardian(self, component: ComponentAddress, guardian: NonFungibleGlobalId, owner: &BadgeHolder) -> Self {
+        self.am_badge_in_auth_zone(owner)
+            .call_method(component, "register_guardian", manifest_args!(guardian))
+    }
+    fn am_remove_guardian(self, component: ComponentAddress, guardian: NonFungibleGlobalId, owner: &BadgeHolder) -> Self {
+        self.am_badge_in_auth_zone(owner)
+            .call_method(component, "remove_guardian", manifest_args!(guardian))
+    }
+
+    fn am_factory_new_manager(self, factory: ComponentAddress, account: ComponentAddress, auth_badge: ResourceAddress, auth_badge_id: NonFungibleLocalId, dapp_definition: ComponentAddress) -> Self {
+        let bucket_name = self.generate_bucket_name("auth_badge");
+        self.withdraw_non_fungibles_from_account(account, auth_badge, [auth_badge_id])
+            .take_all_from_worktop(auth_badge, &bucket_name)
+            .call_method_with_name_lookup(factory, "new_manager", |lookup| (lookup.bucket(&bucket_name), dapp_definition))
+            .try_deposit_entire_worktop_or_abort(account, None)
+    }
+
+    fn am_create_basic_key(self, component: ComponentAddress, include_validator_permissions: bool, proof_of: &BadgeHolder, recipient: ComponentAddress) -> Self {
+        self.am_call_with_proof(component, "create_basic_key_badge", proof_of, |proof| manifest_args!(include_validator_permissions, Some(proof)))
+            .am_deposit_worktop(proof_of, recipient)
+    }
+    fn am_create_custom_key(self, component: ComponentAddress, permissions: &[KeyBadgePermission], proof_of: &BadgeHolder, recipient: ComponentAddress) -> Self {
+        // the blueprint takes the permission names, which are the enum variant names
+        let permissions: Vec<String> = permissions.iter().map(|permission| permission.to_string()).collect();
+        self.am_call_with_proof(component, "create_custom_access_key_badge", proof_of, |proof| manifest_args!(permissions, Some(proof)))
+            .am_deposit_worktop(proof_of, recipient)
+    }
+    fn am_create_custom_key_with_policy(self, component: ComponentAddress, permissions: &[KeyBadgePermission], transfer_policy: KeyTransferPolicy, proof_of: &BadgeHolder, recipient: ComponentAddress) -> Self {
+        let permissions: Vec<String> = permissions.iter().map(|permission| permission.to_string()).collect();
+        self.am_call_with_proof(component, "create_custom_access_key_badge_with_policy", proof_of, |proof| manifest_args!(permissions, transfer_policy, Some(proof)))
+            .am_deposit_worktop(proof_of, recipient)
+    }
+    fn am_create_bound_key(self, component: ComponentAddress, permissions: &[KeyBadgePermission], transfer_policy: KeyTransferPolicy, bound_account: ComponentAddress, proof_of: &BadgeHolder, recipient: ComponentAddress) -> Self {
+        let permissions: Vec<String> = permissions.iter().map(|permission| permission.to_string()).collect();
+        self.am_call_with_proof(component, "create_bound_access_key_badge", proof_of, |proof| manifest_args!(permissions, transfer_policy, bound_account, Some(proof)))
+            .am_deposit_worktop(proof_of, recipient)
+    }
+    fn am_transfer_key(self, component: ComponentAddress, key_badge_vault: InternalAddress, key_id: NonFungibleLocalId, destination: ComponentAddress, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "transfer_key_badge", proof_of, |proof| manifest_args!(key_badge_vault, key_id, destination, proof))
+    }
+    fn am_recall_key(self, component: ComponentAddress, key_badge_vault: InternalAddress, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "recall_key_badge", proof_of, |proof| manifest_args!(key_badge_vault, Some(proof)))
+            .am_deposit_worktop(proof_of, proof_of.account)
+    }
+    fn am_recall_keys_by_id(self, component: ComponentAddress, key_badge_vault: InternalAddress, key_ids: &[NonFungibleLocalId], proof_of: &BadgeHolder) -> Self {
+        let key_ids = key_ids.to_vec();
+        self.am_call_with_proof(component, "recall_key_badges_by_id", proof_of, |proof| manifest_args!(key_badge_vault, key_ids, Some(proof)))
+            .am_deposit_worktop(proof_of, proof_of.account)
+    }
+    fn am_recall_and_burn_key(self, component: ComponentAddress, key_badge: ResourceAddress, key_badge_vault: InternalAddress, proof_of: &BadgeHolder) -> Self {
+        let bucket_name = self.generate_bucket_name("recalled_key_badge");
+        self.am_call_with_proof(component, "recall_key_badge", proof_of, |proof| manifest_args!(key_badge_vault, Some(proof)))
+            .take_all_from_worktop(key_badge, &bucket_name)
+            // `burn_key_badge` is restricted to the owner and key holder roles
+            .am_badge_in_auth_zone(proof_of)
+            .call_method_with_name_lookup(component, "burn_key_badge", |lookup| (lookup.bucket(&bucket_name),))
+    }
+    fn am_recall_and_burn_keys(self, component: ComponentAddress, keys: &[(InternalAddress, Vec<NonFungibleLocalId>)], proof_of: &BadgeHolder) -> Self {
+        let keys = keys.to_vec();
+        self.am_call_with_proof(component, "recall_and_burn_key_badges", proof_of, |proof| manifest_args!(keys, Some(proof)))
+    }
+    fn am_create_auth_badge_proof(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "create_auth_badge_proof", proof_of, |proof| manifest_args!(Some(proof)))
+    }
+    fn am_create_session_key(self, component: ComponentAddress, session_key_badge: ResourceAddress, permissions: &[KeyBadgePermission], proof_of: &BadgeHolder, session_bucket: &str) -> Self {
+        let permissions: Vec<String> = permissions.iter().map(|permission| permission.to_string()).collect();
+        self.am_call_with_proof(component, "create_session_key", proof_of, |proof| manifest_args!(permissions, proof))
+            .take_all_from_worktop(session_key_badge, session_bucket)
+    }
+    fn am_burn_session_key(self, component: ComponentAddress, session_bucket: &str, proof_of: &BadgeHolder) -> Self {
+        // `burn_key_badge` is restricted to the owner and key holder roles
+        self.am_badge_in_auth_zone(proof_of)
+            .call_method_with_name_lookup(component, "burn_key_badge", |lookup| (lookup.bucket(session_bucket),))
+    }
+    fn am_renounce_permissions(self, component: ComponentAddress, permissions: &[KeyBadgePermission], key: &BadgeHolder) -> Self {
+        let permissions: Vec<String> = permissions.iter().map(|permission| permission.to_string()).collect();
+        self.am_call_with_proof(component, "renounce_permissions", key, |proof| manifest_args!(permissions, proof))
+    }
+    fn am_renounce_key(self, component: ComponentAddress, key_badge_vault: InternalAddress, key: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "renounce_key", key, |proof| manifest_args!(key_badge_vault, proof))
+    }
+
+    fn am_deposit_to_treasury(self, component: ComponentAddress, resource: ResourceAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_bucket_and_proof(component, "deposit_to_treasury", resource, amount, proof_of)
+    }
+    fn am_withdraw_from_treasury(self, component: ComponentAddress, resource: ResourceAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "withdraw_from_treasury", proof_of, |proof| manifest_args!(resource, amount, Some(proof)))
+            .try_deposit_entire_worktop_or_abort(proof_of.account, None)
+    }
+
+    fn am_get_auth_badge_info(self, component: ComponentAddress) -> Self {
+        self.call_method(component, "get_auth_badge_info", manifest_args!())
+    }
+    fn am_get_resource_addresses(self, component: ComponentAddress) -> Self {
+        self.call_method(component, "get_resource_addresses", manifest_args!())
+    }
+    fn am_get_validator_address(self, component: ComponentAddress) -> Self {
+        self.call_method(component, "get_validator_address", manifest_args!())
+    }
+    fn am_get_config(self, component: ComponentAddress) -> Self {
+        self.call_method(component, "get_config", manifest_args!())
+    }
+    fn am_get_signature_delegates(self, component: ComponentAddress) -> Self {
+        self.call_method(component, "get_signature_delegates", manifest_args!())
+    }
+    fn am_get_rate_limits(self, component: ComponentAddress) -> Self {
+        self.call_method(component, "get_rate_limits", manifest_args!())
+    }
+    fn am_get_key_rate_limit(self, component: ComponentAddress, key_id: NonFungibleLocalId, permission: &KeyBadgePermission) -> Self {
+        self.call_method(component, "get_key_rate_limit", manifest_args!(key_id, permission.to_string()))
+    }
+    fn am_get_delegate_rate_limit(self, component: ComponentAddress, delegate: NonFungibleGlobalId, permission: &KeyBadgePermission) -> Self {
+        self.call_method(component, "get_delegate_rate_limit", manifest_args!(delegate, permission.to_string()))
+    }
+    fn am_get_validator_operation_delays(self, component: ComponentAddress) -> Self {
+        self.call_method(component, "get_validator_operation_delays", manifest_args!())
+    }
+    fn am_get_guardians(self, component: ComponentAddress) -> Self {
+        self.call_method(component, "get_guardians", manifest_args!())
+    }
+    fn am_get_validator_proposals(self, component: ComponentAddress) -> Self {
+        self.call_method(component, "get_validator_proposals", manifest_args!())
+    }
+    fn am_get_key_burned_at(self, component: ComponentAddress, key_id: NonFungibleLocalId) -> Self {
+        self.call_method(component, "get_key_burned_at", manifest_args!(key_id))
+    }
+    fn am_describe_key_permissions(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "describe_key_permissions", proof_of, |proof| manifest_args!(proof))
+    }
+    fn am_can_call(self, component: ComponentAddress, method_name: &str, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "can_call", proof_of, |proof| manifest_args!(method_name, proof))
+    }
+
+    fn am_validator_register(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_register", proof_of, |proof| manifest_args!(Some(proof)))
+    }
+    fn am_validator_unregister(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_unregister", proof_of, |proof| manifest_args!(Some(proof)))
+    }
+    fn am_validator_stake_as_owner(self, component: ComponentAddress, xrd_amount: Decimal, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_bucket_and_proof(component, "validator_stake_as_owner", XRD, xrd_amount, proof_of)
+            .try_deposit_entire_worktop_or_abort(proof_of.account, None)
+    }
+    fn am_validator_stake_as_owner_to_payout(self, component: ComponentAddress, xrd_amount: Decimal, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_bucket_and_proof(component, "validator_stake_as_owner_to_payout", XRD, xrd_amount, proof_of)
+    }
+    fn am_validator_stake_from_treasury(self, component: ComponentAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_stake_from_treasury", proof_of, |proof| manifest_args!(amount, Some(proof)))
+    }
+    fn am_validator_update_key(self, component: ComponentAddress, key: Secp256k1PublicKey, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_update_key", proof_of, |proof| manifest_args!(key, Some(proof)))
+    }
+    fn am_propose_validator_operation(self, component: ComponentAddress, operation: ValidatorOperation, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "propose_validator_operation", proof_of, |proof| manifest_args!(operation, Some(proof)))
+    }
+    fn am_execute_validator_proposal(self, component: ComponentAddress, proposal_id: u64) -> Self {
+        self.call_method(component, "execute_validator_proposal", manifest_args!(proposal_id))
+    }
+    fn am_veto_validator_proposal(self, component: ComponentAddress, proposal_id: u64, owner: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "veto_validator_proposal", owner, |proof| manifest_args!(proposal_id, Some(proof)))
+    }
+    fn am_validator_update_fee(self, component: ComponentAddress, new_fee_factor: Decimal, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_update_fee", proof_of, |proof| manifest_args!(new_fee_factor, Some(proof)))
+    }
+    fn am_validator_update_accept_delegated_stake(self, component: ComponentAddress, accept_delegated_stake: bool, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_update_accept_delegated_stake", proof_of, |proof| manifest_args!(accept_delegated_stake, Some(proof)))
+    }
+    fn am_validator_signal_protocol_update_readiness(self, component: ComponentAddress, vote: String, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_signal_protocol_update_readiness", proof_of, |proof| manifest_args!(vote, Some(proof)))
+    }
+    fn am_validator_lock_owner_stake_units(self, component: ComponentAddress, stake_unit: ResourceAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_bucket_and_proof(component, "validator_lock_owner_stake_units", stake_unit, amount, proof_of)
+    }
+    fn am_validator_lock_owner_stake_units_from_treasury(self, component: ComponentAddress, amount: Decimal, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_lock_owner_stake_units_from_treasury", proof_of, |proof| manifest_args!(amount, Some(proof)))
+    }
+    fn am_validator_start_unlock_owner_stake_units(self, component: ComponentAddress, requested_stake_unit_amount: Decimal, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_start_unlock_owner_stake_units", proof_of, |proof| manifest_args!(requested_stake_unit_amount, Some(proof)))
+    }
+    fn am_validator_finish_unlock_owner_stake_units(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_finish_unlock_owner_stake_units", proof_of, |proof| manifest_args!(Some(proof)))
+            .try_deposit_entire_worktop_or_abort(proof_of.account, None)
+    }
+    fn am_validator_finish_unlock_owner_stake_units_to_payout(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_finish_unlock_owner_stake_units_to_payout", proof_of, |proof| manifest_args!(Some(proof)))
+    }
+    fn am_validator_compound_owner_stake(self, component: ComponentAddress, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_compound_owner_stake", proof_of, |proof| manifest_args!(Some(proof)))
+    }
+    fn am_validator_set_metadata(self, component: ComponentAddress, key: String, value: String, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_set_metadata", proof_of, |proof| manifest_args!(key, value, Some(proof)))
+    }
+    fn am_validator_lock_metadata(self, component: ComponentAddress, key: String, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_lock_metadata", proof_of, |proof| manifest_args!(key, Some(proof)))
+    }
+    fn am_validator_set_metadata_role(self, component: ComponentAddress, role_name: String, rule: AccessRule, proof_of: &BadgeHolder) -> Self {
+        self.am_call_with_proof(component, "validator_set_metadata_role", proof_of, |proof| manifest_args!(role_name, rule, Some(proof)))
+    }
+}
@@ -1,4 +1,7 @@
-mod access_manager;
+pub mod access_manager;
+pub mod access_manager_factory;
 mod validator_extension;
+#[cfg(feature = "client")]
+pub mod client;
 #[cfg(test)]
 mod test_helpers;
\ No newline at end of file
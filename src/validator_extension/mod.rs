@@ -1,2 +1,3 @@
+#[allow(clippy::module_inception)]
 pub mod validator_extension;
 pub mod custom_types;
\ No newline at end of file
@@ -3,74 +3,329 @@ use crate::validator_extension::custom_types::*; // for ValidatorOwnerBadgeData
 use crate::access_manager::access_manager::access_manager::AccessManager;
 use crate::access_manager::access_manager_helper::*;
 use crate::access_manager::custom_types::*;
+use crate::access_manager::errors::{ensure, AccessManagerError};
+use crate::access_manager::events::*;
 
+fn validator_of(access_manager: &AccessManager) -> Global<Validator> {
+    ensure(!access_manager.auth_badge.is_empty(), AccessManagerError::EmptyAuthVault);
+    let nft_data: ValidatorOwnerBadgeData = access_manager.auth_badge.non_fungible().data();
+    nft_data.validator.into()
+}
+// the validator controlled by the deposited auth badge, `None` without a validator owner badge in the vault
+pub(crate) fn validator_address(access_manager: &AccessManager) -> Option<ComponentAddress> {
+    if access_manager.auth_badge.is_empty() || access_manager.auth_badge.resource_address() != VALIDATOR_OWNER_BADGE {
+        return None;
+    }
+    let nft_data: ValidatorOwnerBadgeData = access_manager.auth_badge.non_fungible().data();
+    Some(nft_data.validator)
+}
 fn validator_authorize<F, O>(access_manager: &AccessManager, f: F) -> O where F: FnOnce(&mut Global<Validator>) -> O,
 {
-    let nft_data: ValidatorOwnerBadgeData = access_manager.auth_badge.non_fungible().data();
-    let validator_address = Some(nft_data.validator);
-    let mut validator: Global<Validator> = validator_address.unwrap().into();
+    let mut validator = validator_of(access_manager);
     let non_fungible_id = access_manager.auth_badge.non_fungible_local_id();
     access_manager
         .auth_badge
         .authorize_with_non_fungibles(&indexset!(non_fungible_id), || f(&mut validator))
 }
-pub(crate) fn register(access_manager: &AccessManager, proof: NonFungibleProof) {
+// deposits a validator output into the owner configured payout account instead of returning it to the caller
+fn deposit_to_payout_account(access_manager: &AccessManager, bucket: Bucket) {
+    let payout_account = access_manager.payout_account.unwrap_or_else(|| AccessManagerError::PayoutAccountNotConfigured.panic());
+    let mut account: Global<Account> = payout_account.into();
+    account.try_deposit_or_abort(bucket, None);
+}
+// the validator publishes its stake unit resource in the locked `pool_unit` metadata entry
+fn stake_unit_address(access_manager: &AccessManager) -> ResourceAddress {
+    let pool_unit: GlobalAddress = validator_of(access_manager)
+        .get_metadata("pool_unit")
+        .unwrap_or_else(|error| AccessManagerError::ValidatorPoolUnitMissing.panic_with(format!("{:?}", error)))
+        .unwrap_or_else(|| AccessManagerError::ValidatorPoolUnitMissing.panic());
+    ResourceAddress::new_or_panic(pool_unit.as_node_id().0)
+}
+// only XRD and the validator's own stake units are accepted, the stake unit vault is created on the first deposit
+fn put_in_treasury(access_manager: &mut AccessManager, bucket: Bucket) {
+    if bucket.resource_address() == XRD {
+        access_manager.xrd_treasury.put(bucket.as_fungible());
+        return;
+    }
+    ensure(bucket.resource_address() == stake_unit_address(access_manager), AccessManagerError::TreasuryResourceNotAccepted);
+    match access_manager.stake_unit_treasury.as_mut() {
+        Some(vault) => vault.put(bucket.as_fungible()),
+        None => access_manager.stake_unit_treasury = Some(FungibleVault::with_bucket(bucket.as_fungible()))
+    }
+}
+pub(crate) fn deposit_to_treasury(access_manager: &mut AccessManager, bucket: Bucket, proof: Option<NonFungibleProof>) {
+    check_caller_permissions(access_manager, KeyBadgePermission::Treasury_Deposit, None, proof);
+    put_in_treasury(access_manager, bucket);
+}
+pub(crate) fn withdraw_from_treasury(access_manager: &mut AccessManager, resource_address: ResourceAddress, amount: Decimal, proof: Option<NonFungibleProof>) -> Bucket {
+    check_caller_permissions(access_manager, KeyBadgePermission::Treasury_Withdraw, None, proof);
+    if resource_address == XRD {
+        return access_manager.xrd_treasury.take(amount).into();
+    }
+    access_manager.stake_unit_treasury
+        .as_mut()
+        .filter(|vault| vault.resource_address() == resource_address)
+        .unwrap_or_else(|| AccessManagerError::TreasuryResourceNotHeld.panic())
+        .take(amount)
+        .into()
+}
+pub(crate) fn register(access_manager: &AccessManager, proof: Option<NonFungibleProof>) {
     check_caller_permissions(access_manager, KeyBadgePermission::Validator_Register, None, proof);
     validator_authorize(access_manager, |validator| {
         validator.register()
     })
 }
-pub(crate) fn unregister(access_manager: &AccessManager, proof: NonFungibleProof) {
+pub(crate) fn unregister(access_manager: &AccessManager, proof: Option<NonFungibleProof>) {
+    assert_not_delayed(access_manager, "validator_unregister", &proof);
     check_caller_permissions(access_manager, KeyBadgePermission::Validator_Unregister, None, proof);
-    validator_authorize(access_manager, |validator| {
-        validator.unregister()
-    })
+    perform_operation(access_manager, ValidatorOperation::Unregister);
 }
-pub(crate) fn stake_as_owner(access_manager: &AccessManager, stake: Bucket, proof: NonFungibleProof) -> Bucket {
+pub(crate) fn stake_as_owner(access_manager: &AccessManager, stake: Bucket, proof: Option<NonFungibleProof>) -> Bucket {
     check_caller_permissions(access_manager, KeyBadgePermission::Validator_StakeAsOwner, None, proof);
     validator_authorize(access_manager, |validator| {
         validator.stake_as_owner(stake)
     })
 }
-pub(crate) fn update_key(access_manager: &AccessManager, key: Secp256k1PublicKey, proof: NonFungibleProof) {
+pub(crate) fn stake_as_owner_to_payout(access_manager: &AccessManager, stake: Bucket, proof: Option<NonFungibleProof>) {
+    check_caller_permissions(access_manager, KeyBadgePermission::Validator_StakeAsOwnerToPayout, None, proof);
+    let stake_units = validator_authorize(access_manager, |validator| {
+        validator.stake_as_owner(stake)
+    });
+    deposit_to_payout_account(access_manager, stake_units);
+}
+pub(crate) fn stake_from_treasury(access_manager: &mut AccessManager, amount: Decimal, proof: Option<NonFungibleProof>) {
+    check_caller_permissions(access_manager, KeyBadgePermission::Validator_StakeFromTreasury, None, proof);
+    let stake = access_manager.xrd_treasury.take(amount);
+    let stake_units = validator_authorize(access_manager, |validator| {
+        validator.stake_as_owner(stake.into())
+    });
+    put_in_treasury(access_manager, stake_units);
+}
+pub(crate) fn update_key(access_manager: &AccessManager, key: Secp256k1PublicKey, proof: Option<NonFungibleProof>) {
+    assert_not_delayed(access_manager, "validator_update_key", &proof);
     check_caller_permissions(access_manager, KeyBadgePermission::Validator_UpdateKey, None, proof);
-    validator_authorize(access_manager, |validator| {
-        validator.update_key(key)
+    perform_operation(access_manager, ValidatorOperation::UpdateKey(key));
+}
+// a delayed method may only be called directly with the owner badge, which `check_caller_permissions` then verifies
+fn assert_not_delayed(access_manager: &AccessManager, method_name: &str, proof: &Option<NonFungibleProof>) {
+    if access_manager.validator_operation_delays.contains_key(method_name) {
+        let owner_badge = proof.as_ref().is_some_and(|proof| proof.resource_manager().address() == access_manager.owner_badge_resource_manager.address());
+        ensure(owner_badge, AccessManagerError::ProposalRequired);
+    }
+}
+fn perform_operation(access_manager: &AccessManager, operation: ValidatorOperation) {
+    validator_authorize(access_manager, |validator| match operation {
+        ValidatorOperation::UpdateKey(key) => validator.update_key(key),
+        ValidatorOperation::Unregister => validator.unregister(),
     })
 }
-pub(crate) fn update_fee(access_manager: &AccessManager, new_fee_factor: Decimal, proof: NonFungibleProof) {
+///
+/// Records `operation` with its proposer. Anyone may execute it with `execute_validator_proposal` once the delay configured
+/// for its method has passed, until then the owner or a guardian may veto it.
+///
+pub(crate) fn propose_operation(access_manager: &mut AccessManager, operation: ValidatorOperation, proof: Option<NonFungibleProof>) -> u64 {
+    let proposer = match check_caller_permissions(access_manager, operation.permission(), None, proof) {
+        CallerBadge::Owner => Proposer::Owner,
+        CallerBadge::AccessKey(key_id, _) => Proposer::AccessKey(key_id),
+        CallerBadge::SignatureDelegate(delegates) => Proposer::SignatureDelegate(delegates),
+        // the proposal outlives the session key, leaving nothing to check again when it is executed
+        CallerBadge::SessionKey(_) => AccessManagerError::NotAuthorized.panic_with("session keys cannot propose validator operations"),
+        CallerBadge::Unknown => AccessManagerError::NotAuthorized.panic()
+    };
+    let proposed_at = Clock::current_time_rounded_to_seconds();
+    let executable_at = delayed_until(access_manager, &operation, proposed_at);
+    let proposal = ValidatorProposal { operation, proposer, proposed_at, executable_at };
+    let proposal_id = access_manager.next_proposal_id;
+    access_manager.next_proposal_id += 1;
+    access_manager.validator_proposals.insert(proposal_id, proposal.clone());
+    Runtime::emit_event(ValidatorOperationProposedEvent { proposal_id, proposal });
+    proposal_id
+}
+// when the delay currently configured for the method of `operation` has passed for a proposal made at `proposed_at`
+fn delayed_until(access_manager: &AccessManager, operation: &ValidatorOperation, proposed_at: Instant) -> Instant {
+    let delay = access_manager.validator_operation_delays.get(operation.method_name()).copied().unwrap_or(0);
+    // `set_validator_operation_delay` bounds the delay, so this only fails for ledger times near the end of `i64`
+    proposed_at.add_seconds(delay).unwrap_or_else(|| AccessManagerError::ProposalDelayTooLong.panic())
+}
+///
+/// Runs a proposal once both the delay at proposal time and the delay configured now have passed, so raising or setting
+/// a delay also holds back proposals queued before. The proposer must still be authorized, see `assert_proposer_authorized`.
+///
+pub(crate) fn execute_proposal(access_manager: &mut AccessManager, proposal_id: u64) {
+    let proposal = access_manager.validator_proposals.get(&proposal_id).cloned().unwrap_or_else(|| AccessManagerError::ProposalNotFound.panic());
+    let current_delay_passed_at = delayed_until(access_manager, &proposal.operation, proposal.proposed_at);
+    let executable_at = proposal.executable_at.seconds_since_unix_epoch.max(current_delay_passed_at.seconds_since_unix_epoch);
+    if Clock::current_time_rounded_to_seconds().seconds_since_unix_epoch < executable_at {
+        AccessManagerError::ProposalNotExecutable.panic_with(format!("executable at {} seconds since the unix epoch", executable_at));
+    }
+    assert_proposer_authorized(access_manager, &proposal);
+    access_manager.validator_proposals.shift_remove(&proposal_id);
+    perform_operation(access_manager, proposal.operation);
+    Runtime::emit_event(ValidatorProposalExecutedEvent { proposal_id });
+}
+///
+/// Checks the proposer against the current state: the key must not be burned and must still hold the permission of the operation,
+/// every signature delegate recorded for the proposal must still be registered with it. Fails with `ProposerNotAuthorized` otherwise.
+/// Recalling a key or removing a delegate drops its proposals right away, see `drop_key_proposals` and `drop_delegate_proposals`.
+///
+fn assert_proposer_authorized(access_manager: &AccessManager, proposal: &ValidatorProposal) {
+    let permission = proposal.operation.permission();
+    let authorized = match &proposal.proposer {
+        Proposer::Owner => true,
+        Proposer::AccessKey(key_id) => {
+            access_manager.burned_keys.get(key_id).is_none()
+                && access_manager.access_key_badge_resource_manager.get_non_fungible_data::<AccessKeyBadgeData>(key_id).permissions.contains(&permission)
+        }
+        Proposer::SignatureDelegate(delegates) => delegates
+            .iter()
+            .all(|delegate| access_manager.signature_delegates.get(delegate).is_some_and(|permissions| permissions.contains(&permission))),
+    };
+    if !authorized {
+        AccessManagerError::ProposerNotAuthorized.panic_with(format!("{:?}", proposal.proposer));
+    }
+}
+///
+/// Drops the pending proposals of the recalled keys `key_ids`, emitting a `ValidatorProposalVetoedEvent` for each.
+///
+pub(crate) fn drop_key_proposals(access_manager: &mut AccessManager, key_ids: &IndexSet<NonFungibleLocalId>) {
+    let dropped: Vec<u64> = access_manager
+        .validator_proposals
+        .iter()
+        .filter(|(_, proposal)| matches!(&proposal.proposer, Proposer::AccessKey(key_id) if key_ids.contains(key_id)))
+        .map(|(proposal_id, _)| *proposal_id)
+        .collect();
+    for proposal_id in dropped {
+        access_manager.validator_proposals.shift_remove(&proposal_id);
+        Runtime::emit_event(ValidatorProposalVetoedEvent { proposal_id });
+    }
+}
+///
+/// Drops the pending proposals `delegate` is recorded for, emitting a `ValidatorProposalVetoedEvent` for each.
+/// The removed delegate may be the one that signed, so proposals it shares with other delegates are dropped as well.
+///
+pub(crate) fn drop_delegate_proposals(access_manager: &mut AccessManager, delegate: &NonFungibleGlobalId) {
+    let dropped: Vec<u64> = access_manager
+        .validator_proposals
+        .iter()
+        .filter(|(_, proposal)| matches!(&proposal.proposer, Proposer::SignatureDelegate(delegates) if delegates.contains(delegate)))
+        .map(|(proposal_id, _)| *proposal_id)
+        .collect();
+    for proposal_id in dropped {
+        access_manager.validator_proposals.shift_remove(&proposal_id);
+        Runtime::emit_event(ValidatorProposalVetoedEvent { proposal_id });
+    }
+}
+///
+/// Drops a pending proposal. Takes the owner badge as the proof, or without a proof the badge of a registered guardian in the auth zone.
+///
+pub(crate) fn veto_proposal(access_manager: &mut AccessManager, proposal_id: u64, proof: Option<NonFungibleProof>) {
+    match proof {
+        Some(proof) => ensure(matches!(identify_caller(access_manager, Some(proof)), CallerBadge::Owner), AccessManagerError::NotAuthorized),
+        None => {
+            let guardians: Vec<ResourceOrNonFungible> = access_manager.guardians.iter().map(|guardian| guardian.clone().into()).collect();
+            ensure(!guardians.is_empty(), AccessManagerError::NotAuthorized);
+            Runtime::assert_access_rule(rule!(require_any_of(guardians)));
+        }
+    }
+    ensure(access_manager.validator_proposals.shift_remove(&proposal_id).is_some(), AccessManagerError::ProposalNotFound);
+    Runtime::emit_event(ValidatorProposalVetoedEvent { proposal_id });
+}
+pub(crate) fn update_fee(access_manager: &AccessManager, new_fee_factor: Decimal, proof: Option<NonFungibleProof>) {
     check_caller_permissions(access_manager, KeyBadgePermission::Validator_UpdateFee, None, proof);
     validator_authorize(access_manager, |validator| {
         validator.update_fee(new_fee_factor)
     })
 }
-pub(crate) fn update_accept_delegated_stake(access_manager: &AccessManager, accept_delegated_stake: bool, proof: NonFungibleProof) {
+pub(crate) fn update_accept_delegated_stake(access_manager: &AccessManager, accept_delegated_stake: bool, proof: Option<NonFungibleProof>) {
     check_caller_permissions(access_manager, KeyBadgePermission::Validator_UpdateAcceptDelegatedStake, None, proof);
     validator_authorize(access_manager, |validator| {
         validator.update_accept_delegated_stake(accept_delegated_stake)
     })
 }
-pub(crate) fn signal_protocol_update_readiness(access_manager: &AccessManager, vote: String, proof: NonFungibleProof) {
+pub(crate) fn signal_protocol_update_readiness(access_manager: &AccessManager, vote: String, proof: Option<NonFungibleProof>) {
     check_caller_permissions(access_manager, KeyBadgePermission::Validator_SignalProtocolUpdateReadiness, None, proof);
     validator_authorize(access_manager, |validator| {
         validator.signal_protocol_update_readiness(vote)
     })
 }
-pub(crate) fn lock_owner_stake_units(access_manager: &AccessManager, stake_unit_bucket: Bucket, proof: NonFungibleProof) {
+pub(crate) fn lock_owner_stake_units(access_manager: &AccessManager, stake_unit_bucket: Bucket, proof: Option<NonFungibleProof>) {
     check_caller_permissions(access_manager, KeyBadgePermission::Validator_LockOwnerStakeUnits, None, proof);
     validator_authorize(access_manager, |validator| {
         validator.lock_owner_stake_units(stake_unit_bucket)
     })
 }
-pub(crate) fn start_unlock_owner_stake_units(access_manager: &AccessManager, requested_stake_unit_amount: Decimal, proof: NonFungibleProof) {
+pub(crate) fn lock_owner_stake_units_from_treasury(access_manager: &mut AccessManager, amount: Decimal, proof: Option<NonFungibleProof>) {
+    check_caller_permissions(access_manager, KeyBadgePermission::Validator_LockOwnerStakeUnitsFromTreasury, None, proof);
+    let stake_unit_bucket = access_manager.stake_unit_treasury
+        .as_mut()
+        .unwrap_or_else(|| AccessManagerError::TreasuryHasNoStakeUnits.panic())
+        .take(amount);
+    validator_authorize(access_manager, |validator| {
+        validator.lock_owner_stake_units(stake_unit_bucket.into())
+    })
+}
+pub(crate) fn start_unlock_owner_stake_units(access_manager: &AccessManager, requested_stake_unit_amount: Decimal, proof: Option<NonFungibleProof>) {
     check_caller_permissions(access_manager, KeyBadgePermission::Validator_StartUnlockOwnerStakeUnits, None, proof);
     validator_authorize(access_manager, |validator| {
         validator.start_unlock_owner_stake_units(requested_stake_unit_amount)
     })
 }
-pub(crate) fn finish_unlock_owner_stake_units(access_manager: &AccessManager, proof: NonFungibleProof) -> Bucket {
+pub(crate) fn finish_unlock_owner_stake_units(access_manager: &AccessManager, proof: Option<NonFungibleProof>) -> Bucket {
     check_caller_permissions(access_manager, KeyBadgePermission::Validator_FinishUnlockOwnerStakeUnits, None, proof);
     validator_authorize(access_manager, |validator| {
         validator.finish_unlock_owner_stake_units()
     })
 }
+pub(crate) fn finish_unlock_owner_stake_units_to_payout(access_manager: &AccessManager, proof: Option<NonFungibleProof>) {
+    check_caller_permissions(access_manager, KeyBadgePermission::Validator_FinishUnlockOwnerStakeUnitsToPayout, None, proof);
+    let unlocked_stake_units = validator_authorize(access_manager, |validator| {
+        validator.finish_unlock_owner_stake_units()
+    });
+    deposit_to_payout_account(access_manager, unlocked_stake_units);
+}
+// finishes the pending owner unlock and locks the stake units again, the funds never leave the component
+// the validator returns the unlocked owner stake as stake units that are still staked, so there is no XRD to stake again
+pub(crate) fn compound_owner_stake(access_manager: &AccessManager, proof: Option<NonFungibleProof>) {
+    check_caller_permissions(access_manager, KeyBadgePermission::Validator_CompoundOwnerStake, None, proof);
+    validator_authorize(access_manager, |validator| {
+        let unlocked_stake_units = validator.finish_unlock_owner_stake_units();
+        ensure(!unlocked_stake_units.is_empty(), AccessManagerError::NothingToCompound);
+        validator.lock_owner_stake_units(unlocked_stake_units)
+    })
+}
+
+// the scoped permission that allows editing a single well-known validator metadata key
+fn metadata_key_permission(key: &str) -> Option<KeyBadgePermission> {
+    match key {
+        "name" => Some(KeyBadgePermission::Validator_SetMetadata_Name),
+        "description" => Some(KeyBadgePermission::Validator_SetMetadata_Description),
+        "info_url" => Some(KeyBadgePermission::Validator_SetMetadata_InfoUrl),
+        "icon_url" => Some(KeyBadgePermission::Validator_SetMetadata_IconUrl),
+        _ => None
+    }
+}
+pub(crate) fn set_metadata(access_manager: &AccessManager, key: String, value: String, proof: Option<NonFungibleProof>) {
+    // `Validator_SetMetadata` allows any key, the scoped permissions only allow their own key
+    let mut allowed_permissions = vec![KeyBadgePermission::Validator_SetMetadata];
+    allowed_permissions.extend(metadata_key_permission(key.as_str()));
+    check_caller_any_permission(access_manager, allowed_permissions, proof);
+    validator_authorize(access_manager, |validator| {
+        // wallets and dashboards read `info_url` and `icon_url` as Url metadata, everything else is stored as a string
+        match key.as_str() {
+            "info_url" | "icon_url" => validator.set_metadata(key, UncheckedUrl::of(value)),
+            _ => validator.set_metadata(key, value)
+        }
+    })
+}
+pub(crate) fn lock_metadata(access_manager: &AccessManager, key: String, proof: Option<NonFungibleProof>) {
+    check_caller_permissions(access_manager, KeyBadgePermission::Validator_LockMetadata, None, proof);
+    validator_authorize(access_manager, |validator| {
+        validator.lock_metadata(key)
+    })
+}
+pub(crate) fn set_metadata_role(access_manager: &AccessManager, role_name: String, rule: AccessRule, proof: Option<NonFungibleProof>) {
+    check_caller_permissions(access_manager, KeyBadgePermission::Validator_SetMetadataRole, None, proof);
+    validator_authorize(access_manager, |validator| {
+        validator.set_metadata_role(role_name.as_str(), rule)
+    })
+}
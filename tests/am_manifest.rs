@@ -0,0 +1,102 @@
+#![cfg(feature = "client")]
+
+use scrypto_test::prelude::*;
+use std::process::{Command, Output};
+
+// Runs the `am-manifest` binary against simulator addresses and checks what it renders or rejects
+
+fn address(node_id: &NodeId) -> String {
+    AddressBech32Encoder::new(&NetworkDefinition::simulator()).encode(node_id.as_bytes()).unwrap()
+}
+
+fn account() -> String {
+    let public_key = Secp256k1PrivateKey::from_u64(1).unwrap().public_key();
+    address(ComponentAddress::virtual_account_from_public_key(&public_key).as_node_id())
+}
+
+fn am_manifest(operation: &str, options: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_am-manifest"))
+        .arg(operation)
+        .args(["--network", "simulator", "--component", &address(FAUCET.as_node_id())])
+        .args(options)
+        .output()
+        .unwrap()
+}
+
+fn rendered(output: Output) -> String {
+    assert!(output.status.success(), "am-manifest failed: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn error(output: Output) -> String {
+    assert!(!output.status.success(), "am-manifest succeeded: {}", String::from_utf8_lossy(&output.stdout));
+    String::from_utf8(output.stderr).unwrap()
+}
+
+fn with_badge<'a>(account: &'a str, badge: &'a str, options: &[&'a str]) -> Vec<&'a str> {
+    let mut all = vec!["--account", account, "--badge", badge, "--badge-id", "#1#"];
+    all.extend_from_slice(options);
+    all
+}
+
+#[test]
+fn execute_validator_proposal_needs_no_badge_or_account() {
+    let manifest = rendered(am_manifest("execute_validator_proposal", &["--proposal-id", "3"]));
+    assert!(manifest.contains("\"execute_validator_proposal\""));
+    assert!(!manifest.contains("lock_fee"));
+}
+
+#[test]
+fn views_need_no_badge() {
+    let account = account();
+    let manifest = rendered(am_manifest("get_config", &["--account", &account]));
+    assert!(manifest.contains("\"get_config\""));
+    assert!(manifest.contains("\"lock_fee\""));
+    let manifest = rendered(am_manifest("get_guardians", &[]));
+    assert!(manifest.contains("\"get_guardians\""));
+}
+
+#[test]
+fn badge_operations_ask_for_the_badge() {
+    let account = account();
+    assert!(error(am_manifest("withdraw_auth_badge", &["--account", &account])).contains("missing --badge"));
+    assert!(error(am_manifest("can_call", &["--method", "withdraw_auth_badge"])).contains("missing --account"));
+}
+
+#[test]
+fn badge_operations_render_with_the_badge() {
+    let (account, badge) = (account(), address(XRD.as_node_id()));
+    let manifest = rendered(am_manifest("can_call", &with_badge(&account, &badge, &["--method", "validator_update_fee"])));
+    assert!(manifest.contains("\"can_call\""));
+    assert!(manifest.contains("\"create_proof_of_non_fungibles\""));
+}
+
+#[test]
+fn set_metadata_role_parses_the_rule() {
+    let (account, badge) = (account(), address(XRD.as_node_id()));
+    let manifest = rendered(am_manifest("validator_set_metadata_role", &with_badge(&account, &badge, &["--role", "metadata_setter", "--rule", "allow_all"])));
+    assert!(manifest.contains("\"validator_set_metadata_role\""));
+    let badge_rule = format!("{}:#2#", badge);
+    rendered(am_manifest("validator_set_metadata_role", &with_badge(&account, &badge, &["--role", "metadata_setter", "--rule", &badge_rule])));
+    assert!(error(am_manifest("validator_set_metadata_role", &with_badge(&account, &badge, &["--role", "metadata_setter", "--rule", "everyone"]))).contains("--rule"));
+}
+
+#[test]
+fn create_session_key_burns_the_session_key_at_the_end() {
+    let (account, badge) = (account(), address(XRD.as_node_id()));
+    let options = with_badge(&account, &badge, &["--session-key-badge", &badge, "--permissions", "Validator_UpdateFee"]);
+    let manifest = rendered(am_manifest("create_session_key", &options));
+    let create = manifest.find("\"create_session_key\"").unwrap();
+    let burn = manifest.find("\"burn_key_badge\"").unwrap();
+    assert!(create < burn);
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    let (account, badge) = (account(), address(XRD.as_node_id()));
+    let options = with_badge(&account, &badge, &["--permissions", "Validator_Everything", "--recipient", &account]);
+    assert!(error(am_manifest("create_custom_access_key_badge", &options)).contains("unknown permission `Validator_Everything`"));
+    assert!(error(am_manifest("execute_validator_proposal", &["--proposal-id"])).contains("missing value for --proposal-id"));
+    assert!(error(am_manifest("execute_validator_proposal", &["--proposal-id", "-1"])).contains("--proposal-id is not a non-negative integer"));
+    assert!(error(am_manifest("launch_rocket", &[])).contains("unknown operation `launch_rocket`"));
+}
@@ -0,0 +1,312 @@
+// Ledger simulator harness shared by the integration test crates
+#![allow(dead_code, clippy::new_without_default)]
+
+use accessmanager::access_manager::errors::AccessManagerError;
+use radix_engine::system::system_db_reader::SystemDatabaseWriter;
+use scrypto_test::prelude::*;
+use std::sync::OnceLock;
+
+// the package is compiled once per test binary and published on every fresh ledger
+static PACKAGE: OnceLock<(Vec<u8>, PackageDefinition)> = OnceLock::new();
+
+// A caller is an account holding either the access manager owner badge or an access key badge
+#[derive(Clone)]
+pub struct Caller {
+    pub public_key: Secp256k1PublicKey,
+    pub account: ComponentAddress,
+    pub badge: ResourceAddress,
+    pub badge_id: NonFungibleLocalId,
+}
+
+pub struct TestContext {
+    pub ledger: DefaultLedgerSimulator,
+    pub package: PackageAddress,
+    pub validator: ComponentAddress,
+    pub validator_badge_id: NonFungibleLocalId,
+    pub component: ComponentAddress,
+    pub owner: Caller,
+    pub key_badge: ResourceAddress,
+}
+
+impl TestContext {
+    // publishes the package, creates a validator and an access manager for its owner badge, and deposits the owner badge into the manager
+    pub fn new() -> Self {
+        let mut ledger = LedgerSimulatorBuilder::new().build();
+        let (public_key, _private_key, account) = ledger.new_allocated_account();
+        let package = ledger.publish_package(
+            PACKAGE.get_or_init(|| Compile::compile(this_package!(), CompileProfile::Fast)).clone(),
+            BTreeMap::new(),
+            OwnerRole::None,
+        );
+
+        let validator = ledger.new_validator_with_pub_key(public_key, account);
+        let validator_badge_id = NonFungibleLocalId::bytes(validator.as_node_id().0).unwrap();
+
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_function(package, "AccessManager", "new", manifest_args!(VALIDATOR_OWNER_BADGE, account))
+            .try_deposit_entire_worktop_or_abort(account, None)
+            .build();
+        let receipt = ledger.execute_manifest(manifest, vec![NonFungibleGlobalId::from_public_key(&public_key)]);
+        let commit = receipt.expect_commit_success();
+        let component = commit.new_component_addresses()[0];
+        let new_resources = commit.new_resource_addresses().clone();
+        let owner_badge = *new_resources
+            .iter()
+            .find(|resource| Self::resource_name(&mut ledger, **resource) == "Access Manager Owner Badge")
+            .expect("owner badge");
+        let key_badge = *new_resources
+            .iter()
+            .find(|resource| Self::resource_name(&mut ledger, **resource) == "Access Manager Access Key Badge")
+            .expect("key badge");
+        let owner_badge_id = Self::first_id_in_account(&mut ledger, account, owner_badge);
+
+        let mut context = Self {
+            ledger,
+            package,
+            validator,
+            validator_badge_id,
+            component,
+            owner: Caller { public_key, account, badge: owner_badge, badge_id: owner_badge_id },
+            key_badge,
+        };
+        context.deposit_auth_badge().expect_commit_success();
+        context
+    }
+
+    pub fn resource_name(ledger: &mut DefaultLedgerSimulator, resource: ResourceAddress) -> String {
+        match ledger.get_metadata(resource.into(), "name") {
+            Some(MetadataValue::String(name)) => name,
+            _ => String::new(),
+        }
+    }
+
+    pub fn first_id_in_account(ledger: &mut DefaultLedgerSimulator, account: ComponentAddress, resource: ResourceAddress) -> NonFungibleLocalId {
+        let vault = ledger.get_component_vaults(account, resource)[0];
+        let (_amount, mut ids) = ledger.inspect_non_fungible_vault(vault).unwrap();
+        ids.next().unwrap()
+    }
+
+    pub fn execute(&mut self, manifest: TransactionManifestV1, caller: &Caller) -> TransactionReceipt {
+        self.ledger.execute_manifest(manifest, vec![NonFungibleGlobalId::from_public_key(&caller.public_key)])
+    }
+
+    // a PUBLIC method call passing the caller's badge as an explicit proof, anything returned is deposited back to the caller
+    // the permission-gated methods take `Option<NonFungibleProof>`, so `args` receives the proof wrapped in `Some`
+    pub fn proof_manifest(&self, caller: &Caller, method: &str, args: impl FnOnce(Option<ManifestProof>) -> ManifestArgs) -> TransactionManifestV1 {
+        ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .create_proof_from_account_of_non_fungibles(caller.account, caller.badge, [caller.badge_id.clone()])
+            .pop_from_auth_zone("proof")
+            .call_method_with_name_lookup(self.component, method, |lookup| args(Some(lookup.proof("proof"))))
+            // the key badge depositor rule requires a key or owner badge in the auth zone
+            .create_proof_from_account_of_non_fungibles(caller.account, caller.badge, [caller.badge_id.clone()])
+            .try_deposit_entire_worktop_or_abort(caller.account, None)
+            .build()
+    }
+
+    // same as `proof_manifest` for methods taking `(bucket, proof)`, the bucket is withdrawn from the caller's account
+    pub fn bucket_and_proof_manifest(&self, caller: &Caller, resource: ResourceAddress, amount: Decimal, method: &str) -> TransactionManifestV1 {
+        ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .withdraw_from_account(caller.account, resource, amount)
+            .take_all_from_worktop(resource, "bucket")
+            .create_proof_from_account_of_non_fungibles(caller.account, caller.badge, [caller.badge_id.clone()])
+            .pop_from_auth_zone("proof")
+            .call_method_with_name_lookup(self.component, method, |lookup| (lookup.bucket("bucket"), Some(lookup.proof("proof"))))
+            .create_proof_from_account_of_non_fungibles(caller.account, caller.badge, [caller.badge_id.clone()])
+            .try_deposit_entire_worktop_or_abort(caller.account, None)
+            .build()
+    }
+
+    pub fn call_with_proof(&mut self, caller: &Caller, method: &str, args: impl FnOnce(Option<ManifestProof>) -> ManifestArgs) -> TransactionReceipt {
+        let manifest = self.proof_manifest(caller, method, args);
+        self.execute(manifest, caller)
+    }
+
+    pub fn call_with_bucket_and_proof(&mut self, caller: &Caller, resource: ResourceAddress, amount: Decimal, method: &str) -> TransactionReceipt {
+        let manifest = self.bucket_and_proof_manifest(caller, resource, amount, method);
+        self.execute(manifest, caller)
+    }
+
+    // calls a method restricted by role, the caller's badge is only put in the auth zone
+    pub fn call_with_auth_zone(&mut self, caller: &Caller, method: &str, args: ManifestArgs) -> TransactionReceipt {
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .create_proof_from_account_of_non_fungibles(caller.account, caller.badge, [caller.badge_id.clone()])
+            .call_method(self.component, method, args)
+            .try_deposit_entire_worktop_or_abort(caller.account, None)
+            .build();
+        self.execute(manifest, caller)
+    }
+
+    // a permission-gated method called without a proof, signed by `public_key` so its signature badge is in the auth zone
+    pub fn call_as_signature_delegate(&mut self, public_key: Secp256k1PublicKey, method: &str, args: ManifestArgs) -> TransactionReceipt {
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_method(self.component, method, args)
+            .build();
+        self.ledger.execute_manifest(manifest, vec![NonFungibleGlobalId::from_public_key(&public_key)])
+    }
+
+    // calls a view method without any badge and decodes what it returns
+    pub fn view<T: ScryptoDecode>(&mut self, method: &str) -> T {
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_method(self.component, method, manifest_args!())
+            .build();
+        self.ledger.execute_manifest(manifest, vec![]).expect_commit_success().output(1)
+    }
+
+    // the `can_call` dry run for the caller's badge
+    pub fn can_call(&mut self, caller: &Caller, method: &str) -> bool {
+        // `can_call` takes the badge proof itself
+        let manifest = self.proof_manifest(caller, "can_call", |proof| manifest_args!(method, proof.unwrap()));
+        self.execute(manifest, caller).expect_commit_success().output(3)
+    }
+
+    // the `get_key_burned_at` view, `None` while the key exists
+    pub fn key_burned_at(&mut self, key_id: &NonFungibleLocalId) -> Option<Instant> {
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_method(self.component, "get_key_burned_at", manifest_args!(key_id))
+            .build();
+        self.ledger.execute_manifest(manifest, vec![]).expect_commit_success().output(1)
+    }
+
+    pub fn deposit_auth_badge(&mut self) -> TransactionReceipt {
+        let owner = self.owner.clone();
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .withdraw_non_fungibles_from_account(owner.account, VALIDATOR_OWNER_BADGE, [self.validator_badge_id.clone()])
+            .take_all_from_worktop(VALIDATOR_OWNER_BADGE, "auth_badge")
+            .create_proof_from_account_of_non_fungibles(owner.account, owner.badge, [owner.badge_id.clone()])
+            .call_method_with_name_lookup(self.component, "deposit_auth_badge", |lookup| (lookup.bucket("auth_badge"),))
+            .build();
+        self.execute(manifest, &owner)
+    }
+
+    // turns a receipt that minted a single key badge into a caller holding that key
+    pub fn key_holder(&mut self, receipt: TransactionReceipt, account: ComponentAddress, public_key: Secp256k1PublicKey) -> Caller {
+        let commit = receipt.expect_commit_success();
+        let badge_id = commit
+            .vault_balance_changes()
+            .values()
+            .find_map(|(resource, change)| match change {
+                BalanceChange::NonFungible { added, .. } if *resource == self.key_badge && !added.is_empty() => added.iter().next().cloned(),
+                _ => None,
+            })
+            .expect("minted key badge");
+        Caller { public_key, account, badge: self.key_badge, badge_id }
+    }
+
+    // mints a key with the given method and moves it to a fresh account
+    pub fn new_key(&mut self, minter: &Caller, method: &str, args: impl FnOnce(Option<ManifestProof>) -> ManifestArgs) -> Caller {
+        let (public_key, _private_key, account) = self.ledger.new_allocated_account();
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .create_proof_from_account_of_non_fungibles(minter.account, minter.badge, [minter.badge_id.clone()])
+            .pop_from_auth_zone("proof")
+            .call_method_with_name_lookup(self.component, method, |lookup| args(Some(lookup.proof("proof"))))
+            .create_proof_from_account_of_non_fungibles(minter.account, minter.badge, [minter.badge_id.clone()])
+            .try_deposit_entire_worktop_or_abort(account, None)
+            .build();
+        let receipt = self.execute(manifest, minter);
+        self.key_holder(receipt, account, public_key)
+    }
+
+    pub fn new_super_key(&mut self) -> Caller {
+        let owner = self.owner.clone();
+        let (public_key, _private_key, account) = self.ledger.new_allocated_account();
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .create_proof_from_account_of_non_fungibles(owner.account, owner.badge, [owner.badge_id.clone()])
+            .call_method(self.component, "create_super_access_key_badge", manifest_args!(true))
+            .try_deposit_entire_worktop_or_abort(account, None)
+            .build();
+        let receipt = self.execute(manifest, &owner);
+        self.key_holder(receipt, account, public_key)
+    }
+
+    pub fn new_custom_key(&mut self, minter: &Caller, permissions: &[&str]) -> Caller {
+        let permissions: Vec<String> = permissions.iter().map(|permission| permission.to_string()).collect();
+        self.new_key(minter, "create_custom_access_key_badge", |proof| manifest_args!(permissions, proof))
+    }
+
+    pub fn new_owner_custom_key(&mut self, permissions: &[&str]) -> Caller {
+        let owner = self.owner.clone();
+        self.new_custom_key(&owner, permissions)
+    }
+
+    // the validator publishes its stake unit resource in the `pool_unit` metadata entry
+    pub fn stake_unit(&mut self) -> ResourceAddress {
+        match self.ledger.get_metadata(self.validator.into(), "pool_unit") {
+            Some(MetadataValue::GlobalAddress(pool_unit)) => ResourceAddress::new_or_panic(pool_unit.as_node_id().0),
+            _ => panic!("validator has no pool_unit metadata"),
+        }
+    }
+
+    // moves ledger time `seconds` forward by rewriting the consensus manager's proposer timestamps, as the
+    // simulator genesis has no active validator to start a new round with
+    pub fn advance_time(&mut self, seconds: i64) {
+        let epoch_milli = self.ledger.get_current_proposer_timestamp_ms() + seconds * 1000;
+        let mut writer = SystemDatabaseWriter::new(self.ledger.substate_db_mut());
+        writer
+            .write_typed_object_field(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::ProposerMilliTimestamp.field_index(),
+                ConsensusManagerProposerMilliTimestampFieldPayload::from_content_source(ProposerMilliTimestampSubstate { epoch_milli }),
+            )
+            .unwrap();
+        writer
+            .write_typed_object_field(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::ProposerMinuteTimestamp.field_index(),
+                ConsensusManagerProposerMinuteTimestampFieldPayload::from_content_source(ProposerMinuteTimestampSubstate {
+                    epoch_minute: (epoch_milli / 60_000) as i32,
+                }),
+            )
+            .unwrap();
+    }
+
+    pub fn key_badge_vault(&mut self, holder: &Caller) -> InternalAddress {
+        let vault = self.ledger.get_component_vaults(holder.account, self.key_badge)[0];
+        InternalAddress::new_or_panic(vault.0)
+    }
+}
+
+// the panic message of a transaction that was committed as a failure
+pub fn panic_message(receipt: &TransactionReceipt) -> Option<String> {
+    match &receipt.result {
+        TransactionResult::Commit(commit) => match &commit.outcome {
+            TransactionOutcome::Failure(RuntimeError::ApplicationError(ApplicationError::PanicMessage(message))) => Some(message.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// the `AccessManagerError` a failed transaction was rejected with, parsed from its panic message
+pub fn panic_error(receipt: &TransactionReceipt) -> Option<AccessManagerError> {
+    panic_message(receipt).as_deref().and_then(AccessManagerError::parse)
+}
+
+pub fn expect_error(receipt: TransactionReceipt, expected: AccessManagerError) {
+    receipt.expect_specific_failure(|error| match error {
+        RuntimeError::ApplicationError(ApplicationError::PanicMessage(panic)) => AccessManagerError::parse(panic) == Some(expected),
+        _ => false,
+    });
+}
+
+pub fn expect_unauthorized(receipt: TransactionReceipt) {
+    receipt.expect_specific_failure(|error| {
+        matches!(error, RuntimeError::SystemModuleError(SystemModuleError::AuthError(AuthError::Unauthorized(..))))
+    });
+}
+
+// rejected by a `Runtime::assert_access_rule` in the component, e.g. an account-bound key presented from another account
+pub fn expect_access_rule_failure(receipt: TransactionReceipt) {
+    receipt.expect_specific_failure(|error| matches!(error, RuntimeError::SystemError(SystemError::AssertAccessRuleFailed)));
+}
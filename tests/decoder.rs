@@ -0,0 +1,67 @@
+#![cfg(feature = "client")]
+
+use accessmanager::access_manager::custom_types::{AccessKeyBadgeData, KeyBadgePermission, KeyTransferPolicy, OwnerBadgeData};
+use accessmanager::client::decoder::*;
+use scrypto_test::prelude::*;
+
+fn key_badge_data() -> AccessKeyBadgeData {
+    AccessKeyBadgeData {
+        manager_component_address: FAUCET,
+        auth_badge_address: XRD,
+        permissions: vec![KeyBadgePermission::Validator_UpdateFee, KeyBadgePermission::CreateNativeProof],
+        transfer_policy: KeyTransferPolicy::Transferable,
+        bound_account: None,
+    }
+}
+
+#[test]
+fn hex_decodes_access_key_badge_data() {
+    let hex = hex::encode(scrypto_encode(&key_badge_data()).unwrap());
+    match decode_badge_hex(&hex).unwrap() {
+        DecodedBadge::AccessKey(data) => assert_eq!(data.permissions, key_badge_data().permissions),
+        DecodedBadge::Owner(_) => panic!("access key badge data decoded as owner badge data"),
+    }
+}
+
+#[test]
+fn hex_decodes_owner_badge_data() {
+    let data = OwnerBadgeData { manager_component_address: FAUCET, auth_badge_address: XRD };
+    let hex = hex::encode(scrypto_encode(&data).unwrap());
+    assert!(matches!(decode_badge_hex(&hex).unwrap(), DecodedBadge::Owner(_)));
+}
+
+#[test]
+fn programmatic_json_accepts_variant_names_and_ids() {
+    let network = NetworkDefinition::simulator();
+    let encoder = AddressBech32Encoder::new(&network);
+    let json = format!(
+        r#"{{"kind": "Tuple", "fields": [
+            {{"kind": "Reference", "value": "{}"}},
+            {{"kind": "Reference", "value": "{}"}},
+            {{"kind": "Array", "element_kind": "Enum", "elements": [
+                {{"kind": "Enum", "variant_id": "11", "fields": []}},
+                {{"kind": "Enum", "variant_id": 2, "variant_name": "CreateNativeProof", "fields": []}}
+            ]}},
+            {{"kind": "Enum", "variant_id": 1, "fields": []}},
+            {{"kind": "Enum", "variant_id": 1, "variant_name": "Some", "fields": [
+                {{"kind": "Reference", "value": "{}"}}
+            ]}}
+        ]}}"#,
+        FAUCET.display(&encoder),
+        XRD.display(&encoder),
+        FAUCET.display(&encoder)
+    );
+
+    let badge = decode_badge_programmatic_json(&json, &network).unwrap();
+    match &badge {
+        DecodedBadge::AccessKey(data) => {
+            assert_eq!(data.permissions, key_badge_data().permissions);
+            assert_eq!(data.transfer_policy, KeyTransferPolicy::Transferable);
+            assert_eq!(data.bound_account, Some(FAUCET));
+        }
+        DecodedBadge::Owner(_) => panic!("five fields decoded as owner badge data"),
+    }
+    let report = describe_badge(&badge, &network);
+    assert!(report.contains("Validator_UpdateFee\n"));
+    assert!(report.contains("CreateNativeProof [DANGEROUS"));
+}
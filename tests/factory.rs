@@ -0,0 +1,122 @@
+mod common;
+
+use common::*;
+use scrypto_test::prelude::*;
+
+// A factory next to the manager of `TestContext`, with the validator owner badge back in the owner's account
+struct FactoryContext {
+    context: TestContext,
+    factory: ComponentAddress,
+}
+
+impl FactoryContext {
+    fn new(prevent_duplicates: bool) -> Self {
+        let mut context = TestContext::new();
+        let owner = context.owner.clone();
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_function(context.package, "AccessManagerFactory", "instantiate", manifest_args!(prevent_duplicates, owner.account))
+            .build();
+        let factory = context.execute(manifest, &owner).expect_commit_success().new_component_addresses()[0];
+        context.call_with_auth_zone(&owner, "withdraw_auth_badge", manifest_args!()).expect_commit_success();
+        Self { context, factory }
+    }
+
+    fn auth_badge(&self) -> NonFungibleGlobalId {
+        NonFungibleGlobalId::new(VALIDATOR_OWNER_BADGE, self.context.validator_badge_id.clone())
+    }
+
+    // passes the validator owner badge to `new_manager`, returns the new manager and its owner badge resource
+    fn new_manager(&mut self) -> Result<(ComponentAddress, ResourceAddress), Box<TransactionReceipt>> {
+        let owner = self.context.owner.clone();
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .withdraw_non_fungibles_from_account(owner.account, VALIDATOR_OWNER_BADGE, [self.context.validator_badge_id.clone()])
+            .take_all_from_worktop(VALIDATOR_OWNER_BADGE, "auth_badge")
+            .call_method_with_name_lookup(self.factory, "new_manager", |lookup| (lookup.bucket("auth_badge"), owner.account))
+            .try_deposit_entire_worktop_or_abort(owner.account, None)
+            .build();
+        let receipt = self.context.execute(manifest, &owner);
+        if !receipt.is_commit_success() {
+            return Err(Box::new(receipt));
+        }
+        let commit = receipt.expect_commit_success();
+        let manager = commit.new_component_addresses()[0];
+        let owner_badge = *commit
+            .new_resource_addresses()
+            .iter()
+            .find(|resource| TestContext::resource_name(&mut self.context.ledger, **resource) == "Access Manager Owner Badge")
+            .expect("owner badge");
+        Ok((manager, owner_badge))
+    }
+
+    // the owner of `manager` withdraws the auth badge back into their account
+    fn withdraw_auth_badge(&mut self, manager: ComponentAddress, owner_badge: ResourceAddress) {
+        let owner = self.context.owner.clone();
+        let owner_badge_id = TestContext::first_id_in_account(&mut self.context.ledger, owner.account, owner_badge);
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .create_proof_from_account_of_non_fungibles(owner.account, owner_badge, [owner_badge_id])
+            .call_method(manager, "withdraw_auth_badge", manifest_args!())
+            .try_deposit_entire_worktop_or_abort(owner.account, None)
+            .build();
+        self.context.execute(manifest, &owner).expect_commit_success();
+    }
+
+    fn lookup<T: ScryptoDecode>(&mut self, method: &str, args: ManifestArgs) -> T {
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_method(self.factory, method, args)
+            .build();
+        self.context.ledger.execute_manifest(manifest, vec![]).expect_commit_success().output(1)
+    }
+}
+
+#[test]
+fn new_manager_deposits_the_auth_badge_and_registers_the_manager() {
+    let mut factory = FactoryContext::new(true);
+    let (manager, owner_badge) = factory.new_manager().expect("new_manager");
+
+    assert_eq!(factory.context.ledger.get_component_vaults(manager, VALIDATOR_OWNER_BADGE).len(), 1);
+    let auth_badge = factory.auth_badge();
+    assert_eq!(factory.lookup::<Option<ComponentAddress>>("get_manager_by_auth_badge", manifest_args!(auth_badge)), Some(manager));
+    assert_eq!(factory.lookup::<Option<ComponentAddress>>("get_manager_by_owner_badge", manifest_args!(owner_badge)), Some(manager));
+    assert_eq!(factory.lookup::<u64>("get_manager_count", manifest_args!()), 1);
+}
+
+#[test]
+fn withdrawing_the_auth_badge_clears_the_registration() {
+    let mut factory = FactoryContext::new(true);
+    let (manager, owner_badge) = factory.new_manager().expect("new_manager");
+    factory.withdraw_auth_badge(manager, owner_badge);
+
+    let auth_badge = factory.auth_badge();
+    assert_eq!(factory.lookup::<Option<ComponentAddress>>("get_manager_by_auth_badge", manifest_args!(auth_badge)), None);
+    assert_eq!(factory.lookup::<Option<ComponentAddress>>("get_manager_by_owner_badge", manifest_args!(owner_badge)), None);
+}
+
+#[test]
+fn stale_registrations_are_replaced_when_duplicates_are_prevented() {
+    let mut factory = FactoryContext::new(true);
+    let (first_manager, first_owner_badge) = factory.new_manager().expect("first new_manager");
+    factory.withdraw_auth_badge(first_manager, first_owner_badge);
+    let (second_manager, second_owner_badge) = factory.new_manager().expect("the first registration is stale");
+
+    let auth_badge = factory.auth_badge();
+    assert_eq!(factory.lookup::<Option<ComponentAddress>>("get_manager_by_auth_badge", manifest_args!(auth_badge)), Some(second_manager));
+    assert_eq!(factory.lookup::<Option<ComponentAddress>>("get_manager_by_owner_badge", manifest_args!(first_owner_badge)), None);
+    assert_eq!(factory.lookup::<Option<ComponentAddress>>("get_manager_by_owner_badge", manifest_args!(second_owner_badge)), Some(second_manager));
+    assert_eq!(factory.lookup::<u64>("get_manager_count", manifest_args!()), 2);
+}
+
+#[test]
+fn latest_manager_replaces_the_registration_when_duplicates_are_allowed() {
+    let mut factory = FactoryContext::new(false);
+    let (first_manager, first_owner_badge) = factory.new_manager().expect("first new_manager");
+    factory.withdraw_auth_badge(first_manager, first_owner_badge);
+    let (second_manager, _) = factory.new_manager().expect("second new_manager");
+
+    let auth_badge = factory.auth_badge();
+    assert_eq!(factory.lookup::<Option<ComponentAddress>>("get_manager_by_auth_badge", manifest_args!(auth_badge)), Some(second_manager));
+    assert_eq!(factory.lookup::<u64>("get_manager_count", manifest_args!()), 2);
+}
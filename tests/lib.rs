@@ -1,68 +1,1149 @@
-// use scrypto_test::prelude::*;
-
-// use accessmanager::hello_test::*;
-
-// #[test]
-// fn test_hello() {
-//     // Setup the environment
-//     let mut ledger = LedgerSimulatorBuilder::new().build();
-
-//     // Create an account
-//     let (public_key, _private_key, account) = ledger.new_allocated_account();
-
-//     // Publish package
-//     let package_address = ledger.compile_and_publish(this_package!());
-
-//     // Test the `instantiate_hello` function.
-//     let manifest = ManifestBuilder::new()
-//         .lock_fee_from_faucet()
-//         .call_function(
-//             package_address,
-//             "Hello",
-//             "instantiate_hello",
-//             manifest_args!(),
-//         )
-//         .build();
-//     let receipt = ledger.execute_manifest(
-//         manifest,
-//         vec![NonFungibleGlobalId::from_public_key(&public_key)],
-//     );
-//     println!("{:?}\n", receipt);
-//     let component = receipt.expect_commit(true).new_component_addresses()[0];
-
-//     // Test the `free_token` method.
-//     let manifest = ManifestBuilder::new()
-//         .lock_fee_from_faucet()
-//         .call_method(component, "free_token", manifest_args!())
-//         .call_method(
-//             account,
-//             "deposit_batch",
-//             manifest_args!(ManifestExpression::EntireWorktop),
-//         )
-//         .build();
-//     let receipt = ledger.execute_manifest(
-//         manifest,
-//         vec![NonFungibleGlobalId::from_public_key(&public_key)],
-//     );
-//     println!("{:?}\n", receipt);
-//     receipt.expect_commit_success();
-// }
-
-// #[test]
-// fn test_hello_with_test_environment() -> Result<(), RuntimeError> {
-//     // Arrange
-//     let mut env = TestEnvironment::new();
-//     let package_address = 
-//         PackageFactory::compile_and_publish(this_package!(), &mut env, CompileProfile::Fast)?;
-
-//     let mut hello = Hello::instantiate_hello(package_address, &mut env)?;
-
-//     // Act
-//     let bucket = hello.free_token(&mut env)?;
-
-//     // Assert
-//     let amount = bucket.amount(&mut env)?;
-//     assert_eq!(amount, dec!("1"));
-
-//     Ok(())
-// }
+mod common;
+
+use accessmanager::access_manager::custom_types::{
+    AccessManagerConfig, AuthBadgeInfo, KeyBadgePermission, KeyTransferPolicy, Proposer, RateLimit, RateLimitWindow, ResourceAddresses, ValidatorOperation, ValidatorProposal,
+    MAX_VALIDATOR_OPERATION_DELAY_SECONDS,
+};
+use accessmanager::access_manager::errors::AccessManagerError;
+use accessmanager::access_manager::events::KeyBadgesRecalledAndBurnedEvent;
+use common::*;
+use scrypto_test::prelude::*;
+use strum::IntoEnumIterator;
+
+// instantiation and the auth badge vault
+
+#[test]
+fn owner_can_withdraw_and_redeposit_auth_badge() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+
+    context.call_with_auth_zone(&owner, "withdraw_auth_badge", manifest_args!()).expect_commit_success();
+    context.deposit_auth_badge().expect_commit_success();
+}
+
+#[test]
+fn deposit_auth_badge_fails_when_already_deposited() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let second_validator = context.ledger.new_validator_with_pub_key(Secp256k1PrivateKey::from_u64(7).unwrap().public_key(), owner.account);
+    let second_badge_id = NonFungibleLocalId::bytes(second_validator.as_node_id().0).unwrap();
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .withdraw_non_fungibles_from_account(owner.account, VALIDATOR_OWNER_BADGE, [second_badge_id])
+        .take_all_from_worktop(VALIDATOR_OWNER_BADGE, "auth_badge")
+        .create_proof_from_account_of_non_fungibles(owner.account, owner.badge, [owner.badge_id.clone()])
+        .call_method_with_name_lookup(context.component, "deposit_auth_badge", |lookup| (lookup.bucket("auth_badge"),))
+        .build();
+    let receipt = context.execute(manifest, &owner);
+    expect_error(receipt, AccessManagerError::AuthBadgeAlreadyPresent);
+}
+
+#[test]
+fn withdraw_auth_badge_fails_with_empty_vault() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    context.call_with_auth_zone(&owner, "withdraw_auth_badge", manifest_args!()).expect_commit_success();
+
+    let receipt = context.call_with_auth_zone(&owner, "withdraw_auth_badge", manifest_args!());
+    expect_error(receipt, AccessManagerError::EmptyAuthVault);
+}
+
+#[test]
+fn new_with_auth_badge_records_verified_custody() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    assert_eq!(context.ledger.get_metadata(context.component.into(), "auth_badge_custody_verified"), Some(MetadataValue::Bool(false)));
+    context.call_with_auth_zone(&owner, "withdraw_auth_badge", manifest_args!()).expect_commit_success();
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .withdraw_non_fungibles_from_account(owner.account, VALIDATOR_OWNER_BADGE, [context.validator_badge_id.clone()])
+        .take_all_from_worktop(VALIDATOR_OWNER_BADGE, "auth_badge")
+        .call_function_with_name_lookup(context.package, "AccessManager", "new_with_auth_badge", |lookup| (lookup.bucket("auth_badge"), owner.account))
+        .try_deposit_entire_worktop_or_abort(owner.account, None)
+        .build();
+    let receipt = context.execute(manifest, &owner);
+    let commit = receipt.expect_commit_success();
+    let manager = commit.new_component_addresses()[0];
+    let owner_badge = *commit
+        .new_resource_addresses()
+        .iter()
+        .find(|resource| TestContext::resource_name(&mut context.ledger, **resource) == "Access Manager Owner Badge")
+        .expect("owner badge");
+
+    assert_eq!(context.ledger.get_component_vaults(manager, VALIDATOR_OWNER_BADGE).len(), 1);
+    assert_eq!(context.ledger.get_metadata(manager.into(), "auth_badge_custody_verified"), Some(MetadataValue::Bool(true)));
+    assert_eq!(context.ledger.get_metadata(owner_badge.into(), "auth_badge_custody_verified"), Some(MetadataValue::Bool(true)));
+}
+
+#[test]
+fn key_holders_cannot_use_owner_only_methods() {
+    let mut context = TestContext::new();
+    let super_key = context.new_super_key();
+
+    expect_unauthorized(context.call_with_auth_zone(&super_key, "withdraw_auth_badge", manifest_args!()));
+    expect_unauthorized(context.call_with_auth_zone(&super_key, "create_super_access_key_badge", manifest_args!(true)));
+    expect_unauthorized(context.call_with_auth_zone(&super_key, "set_payout_account", manifest_args!(Some(super_key.account))));
+}
+
+#[test]
+fn badges_of_other_resources_are_rejected() {
+    let mut context = TestContext::new();
+    let (public_key, _private_key, account) = context.ledger.new_allocated_account();
+    let other_badge = context.ledger.create_non_fungible_resource(account);
+    let stranger = Caller { public_key, account, badge: other_badge, badge_id: NonFungibleLocalId::integer(1) };
+
+    let receipt = context.call_with_proof(&stranger, "create_auth_badge_proof", |proof| manifest_args!(proof));
+    expect_error(receipt, AccessManagerError::NotAuthorized);
+}
+
+// view methods
+
+#[test]
+fn views_report_the_component_state() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+
+    let config: AccessManagerConfig = context.view("get_config");
+    assert!(config.auth_badge.deposited);
+    assert_eq!(config.auth_badge.resource_address, VALIDATOR_OWNER_BADGE);
+    assert_eq!(config.auth_badge.local_id, Some(context.validator_badge_id.clone()));
+    assert_eq!(config.resources.owner_badge, owner.badge);
+    assert_eq!(config.resources.access_key_badge, context.key_badge);
+    assert_eq!(config.validator, Some(context.validator));
+    assert_eq!(config.payout_account, None);
+    assert_eq!(config.xrd_treasury_amount, Decimal::ZERO);
+    assert!(config.stake_unit_treasury.is_none());
+
+    context.call_with_auth_zone(&owner, "withdraw_auth_badge", manifest_args!()).expect_commit_success();
+    let auth_badge: AuthBadgeInfo = context.view("get_auth_badge_info");
+    assert!(!auth_badge.deposited);
+    assert_eq!(auth_badge.local_id, None);
+    assert_eq!(context.view::<Option<ComponentAddress>>("get_validator_address"), None);
+}
+
+#[test]
+fn describe_key_permissions_lists_the_unlocked_methods() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let key = context.new_owner_custom_key(&["Validator_UpdateFee", "Validator_SetMetadata_Name"]);
+
+    let receipt = context.call_with_proof(&key, "describe_key_permissions", |proof| manifest_args!(proof.unwrap()));
+    let described: Vec<(KeyBadgePermission, Vec<String>)> = receipt.expect_commit_success().output(3);
+    assert_eq!(described, vec![
+        (KeyBadgePermission::Validator_UpdateFee, vec!["validator_update_fee".to_string()]),
+        (KeyBadgePermission::Validator_SetMetadata_Name, vec!["validator_set_metadata".to_string()]),
+    ]);
+
+    let receipt = context.call_with_proof(&owner, "describe_key_permissions", |proof| manifest_args!(proof.unwrap()));
+    let described: Vec<(KeyBadgePermission, Vec<String>)> = receipt.expect_commit_success().output(3);
+    assert_eq!(described.len(), KeyBadgePermission::iter().count());
+}
+
+#[test]
+fn can_call_follows_roles_for_owner_only_methods() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let super_key = context.new_super_key();
+
+    assert!(context.can_call(&owner, "withdraw_auth_badge"));
+    assert!(!context.can_call(&super_key, "withdraw_auth_badge"));
+    assert!(context.can_call(&super_key, "burn_key_badge"));
+    assert!(context.can_call(&super_key, "get_config"));
+    assert!(!context.can_call(&owner, "no_such_method"));
+}
+
+// key creation
+
+#[test]
+fn owner_creates_super_basic_and_custom_keys() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+
+    context.new_super_key();
+    context.new_key(&owner, "create_basic_key_badge", |proof| manifest_args!(false, proof));
+    context.new_key(&owner, "create_basic_key_badge", |proof| manifest_args!(true, proof));
+    context.new_owner_custom_key(&["CreateAccessKey", "RecallAccessKey", "Validator_UpdateFee"]);
+}
+
+// the permissions added after v2 (payout, treasury, compounding, metadata) are only granted through custom keys
+#[test]
+fn bundled_keys_keep_their_v2_permissions() {
+    use KeyBadgePermission::*;
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let super_key = context.new_super_key();
+    let basic_key = context.new_key(&owner, "create_basic_key_badge", |proof| manifest_args!(true, proof));
+    let validator_permissions = [
+        Validator_Register, Validator_Unregister, Validator_StakeAsOwner, Validator_UpdateKey, Validator_UpdateFee, Validator_UpdateAcceptDelegatedStake,
+        Validator_SignalProtocolUpdateReadiness, Validator_LockOwnerStakeUnits, Validator_StartUnlockOwnerStakeUnits, Validator_FinishUnlockOwnerStakeUnits,
+    ];
+
+    let mut held = |key: &Caller| -> IndexSet<KeyBadgePermission> {
+        let receipt = context.call_with_proof(key, "describe_key_permissions", |proof| manifest_args!(proof.unwrap()));
+        let described: Vec<(KeyBadgePermission, Vec<String>)> = receipt.expect_commit_success().output(3);
+        described.into_iter().map(|(permission, _)| permission).collect()
+    };
+    assert_eq!(held(&super_key), [CreateAccessKey, RecallAccessKey, CreateNativeProof].into_iter().chain(validator_permissions.clone()).collect::<IndexSet<_>>());
+    assert_eq!(held(&basic_key), [CreateNativeProof].into_iter().chain(validator_permissions).collect::<IndexSet<_>>());
+}
+
+#[test]
+fn super_key_can_create_basic_and_custom_keys() {
+    let mut context = TestContext::new();
+    let super_key = context.new_super_key();
+
+    context.new_key(&super_key, "create_basic_key_badge", |proof| manifest_args!(true, proof));
+    context.new_custom_key(&super_key, &["CreateNativeProof", "Validator_UpdateFee"]);
+}
+
+#[test]
+fn key_without_create_access_key_cannot_create_keys() {
+    let mut context = TestContext::new();
+    let basic_key = context.new_owner_custom_key(&["CreateNativeProof"]);
+
+    let receipt = context.call_with_proof(&basic_key, "create_basic_key_badge", |proof| manifest_args!(false, proof));
+    expect_error(receipt, AccessManagerError::NotAuthorized);
+}
+
+#[test]
+fn key_holder_cannot_grant_super_permissions() {
+    let mut context = TestContext::new();
+    let super_key = context.new_super_key();
+
+    let permissions = vec![String::from("RecallAccessKey")];
+    let receipt = context.call_with_proof(&super_key, "create_custom_access_key_badge", |proof| manifest_args!(permissions, proof));
+    expect_error(receipt, AccessManagerError::SuperPermissionForbidden);
+}
+
+#[test]
+fn key_holder_cannot_grant_permissions_they_do_not_hold() {
+    let mut context = TestContext::new();
+    let creator = context.new_owner_custom_key(&["CreateAccessKey", "CreateNativeProof"]);
+
+    let permissions = vec![String::from("Validator_UpdateFee")];
+    let receipt = context.call_with_proof(&creator, "create_custom_access_key_badge", |proof| manifest_args!(permissions, proof));
+    expect_error(receipt, AccessManagerError::MissingGrantedPermission);
+
+    let receipt = context.call_with_proof(&creator, "create_basic_key_badge", |proof| manifest_args!(true, proof));
+    expect_error(receipt, AccessManagerError::MissingGrantedPermission);
+}
+
+#[test]
+fn invalid_permission_string_is_rejected() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+
+    let permissions = vec![String::from("UpdateFee")];
+    let receipt = context.call_with_proof(&owner, "create_custom_access_key_badge", |proof| manifest_args!(permissions, proof));
+    expect_error(receipt, AccessManagerError::InvalidPermissionString);
+}
+
+// recall and burn
+
+#[test]
+fn recall_and_burn_key_badge() {
+    let mut context = TestContext::new();
+    let super_key = context.new_super_key();
+    let delegate = context.new_owner_custom_key(&["CreateNativeProof"]);
+    let vault = context.key_badge_vault(&delegate);
+    assert_eq!(context.key_burned_at(&delegate.badge_id), None);
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(super_key.account, super_key.badge, [super_key.badge_id.clone()])
+        .pop_from_auth_zone("proof")
+        .call_method_with_name_lookup(context.component, "recall_key_badge", |lookup| (vault, Some(lookup.proof("proof"))))
+        .take_all_from_worktop(context.key_badge, "recalled")
+        .create_proof_from_account_of_non_fungibles(super_key.account, super_key.badge, [super_key.badge_id.clone()])
+        .call_method_with_name_lookup(context.component, "burn_key_badge", |lookup| (lookup.bucket("recalled"),))
+        .build();
+    let receipt = context.execute(manifest, &super_key);
+    let events: Vec<String> = receipt.expect_commit_success().application_events.iter().map(|(event, _)| context.ledger.event_name(event)).collect();
+    assert!(events.contains(&"KeyBadgesBurnedEvent".to_string()));
+    // the registry records the burned key only
+    assert!(context.key_burned_at(&delegate.badge_id).is_some());
+    assert_eq!(context.key_burned_at(&super_key.badge_id), None);
+
+    // the recalled key can no longer be used
+    context.call_with_proof(&delegate, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_failure();
+}
+
+#[test]
+fn burn_key_badge_rejects_foreign_resources() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let foreign = context.ledger.create_non_fungible_resource(owner.account);
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .withdraw_non_fungibles_from_account(owner.account, foreign, [NonFungibleLocalId::integer(1)])
+        .take_all_from_worktop(foreign, "foreign")
+        .create_proof_from_account_of_non_fungibles(owner.account, owner.badge, [owner.badge_id.clone()])
+        .call_method_with_name_lookup(context.component, "burn_key_badge", |lookup| (lookup.bucket("foreign"),))
+        .build();
+    expect_error(context.execute(manifest, &owner), AccessManagerError::NotAKeyBadge);
+}
+
+#[test]
+fn recall_requires_recall_permission() {
+    let mut context = TestContext::new();
+    let basic_key = context.new_owner_custom_key(&["CreateAccessKey", "CreateNativeProof"]);
+    let delegate = context.new_owner_custom_key(&["CreateNativeProof"]);
+    let vault = context.key_badge_vault(&delegate);
+
+    let receipt = context.call_with_proof(&basic_key, "recall_key_badge", |proof| manifest_args!(vault, proof));
+    expect_error(receipt, AccessManagerError::NotAuthorized);
+}
+
+#[test]
+fn recall_by_id_only_recalls_the_given_key() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let super_key = context.new_super_key();
+    let compromised = context.new_owner_custom_key(&["CreateNativeProof"]);
+
+    // a second key in the same vault
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(owner.account, owner.badge, [owner.badge_id.clone()])
+        .pop_from_auth_zone("proof")
+        .call_method_with_name_lookup(context.component, "create_custom_access_key_badge", |lookup| (vec!["CreateNativeProof"], Some(lookup.proof("proof"))))
+        .create_proof_from_account_of_non_fungibles(owner.account, owner.badge, [owner.badge_id.clone()])
+        .try_deposit_entire_worktop_or_abort(compromised.account, None)
+        .build();
+    let receipt = context.execute(manifest, &owner);
+    let second = context.key_holder(receipt, compromised.account, compromised.public_key);
+    let vault = context.key_badge_vault(&compromised);
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(super_key.account, super_key.badge, [super_key.badge_id.clone()])
+        .pop_from_auth_zone("proof")
+        .call_method_with_name_lookup(context.component, "recall_key_badges_by_id", |lookup| (vault, vec![compromised.badge_id.clone()], Some(lookup.proof("proof"))))
+        .take_all_from_worktop(context.key_badge, "recalled")
+        .create_proof_from_account_of_non_fungibles(super_key.account, super_key.badge, [super_key.badge_id.clone()])
+        .call_method_with_name_lookup(context.component, "burn_key_badge", |lookup| (lookup.bucket("recalled"),))
+        .build();
+    context.execute(manifest, &super_key).expect_commit_success();
+
+    context.call_with_proof(&compromised, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_failure();
+    context.call_with_proof(&second, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_success();
+}
+
+#[test]
+fn recall_and_burn_key_badges_across_vaults() {
+    let mut context = TestContext::new();
+    let super_key = context.new_super_key();
+    let first = context.new_owner_custom_key(&["CreateNativeProof"]);
+    let second = context.new_owner_custom_key(&["CreateNativeProof"]);
+    let keys = vec![
+        (context.key_badge_vault(&first), vec![first.badge_id.clone()]),
+        (context.key_badge_vault(&second), vec![second.badge_id.clone()]),
+    ];
+
+    let receipt = context.call_with_proof(&super_key, "recall_and_burn_key_badges", |proof| manifest_args!(keys, proof));
+    let events: Vec<KeyBadgesRecalledAndBurnedEvent> = receipt
+        .expect_commit_success()
+        .application_events
+        .iter()
+        .filter(|(event, _)| context.ledger.event_name(event) == "KeyBadgesRecalledAndBurnedEvent")
+        .map(|(_, data)| scrypto_decode(data).unwrap())
+        .collect();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].key_ids, vec![first.badge_id.clone(), second.badge_id.clone()]);
+    assert!(context.key_burned_at(&first.badge_id).is_some());
+    assert!(context.key_burned_at(&second.badge_id).is_some());
+
+    context.call_with_proof(&first, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_failure();
+    context.call_with_proof(&second, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_failure();
+}
+
+#[test]
+fn recall_by_id_with_only_recall_permission() {
+    let mut context = TestContext::new();
+    let recaller = context.new_owner_custom_key(&["RecallAccessKey"]);
+    let key = context.new_owner_custom_key(&["CreateNativeProof"]);
+    let vault = context.key_badge_vault(&key);
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(recaller.account, recaller.badge, [recaller.badge_id.clone()])
+        .pop_from_auth_zone("proof")
+        .call_method_with_name_lookup(context.component, "recall_key_badges_by_id", |lookup| (vault, vec![key.badge_id.clone()], Some(lookup.proof("proof"))))
+        .take_all_from_worktop(context.key_badge, "recalled")
+        .create_proof_from_account_of_non_fungibles(recaller.account, recaller.badge, [recaller.badge_id.clone()])
+        .call_method_with_name_lookup(context.component, "burn_key_badge", |lookup| (lookup.bucket("recalled"),))
+        .build();
+    context.execute(manifest, &recaller).expect_commit_success();
+
+    context.call_with_proof(&key, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_failure();
+}
+
+#[test]
+fn recall_by_id_requires_recall_permission() {
+    let mut context = TestContext::new();
+    let basic_key = context.new_owner_custom_key(&["CreateAccessKey", "CreateNativeProof"]);
+    let delegate = context.new_owner_custom_key(&["CreateNativeProof"]);
+    let vault = context.key_badge_vault(&delegate);
+
+    let receipt = context.call_with_proof(&basic_key, "recall_key_badges_by_id", |proof| manifest_args!(vault, vec![delegate.badge_id.clone()], proof));
+    expect_error(receipt, AccessManagerError::NotAuthorized);
+}
+
+// renouncing
+
+#[test]
+fn key_holder_renounces_permissions() {
+    let mut context = TestContext::new();
+    let key = context.new_owner_custom_key(&["Validator_UpdateFee", "CreateNativeProof"]);
+
+    context.call_with_proof(&key, "renounce_permissions", |proof| manifest_args!(vec!["Validator_UpdateFee"], proof.unwrap())).expect_commit_success();
+
+    expect_error(context.call_with_proof(&key, "validator_update_fee", |proof| manifest_args!(dec!("0.02"), proof)), AccessManagerError::NotAuthorized);
+    context.call_with_proof(&key, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_success();
+}
+
+#[test]
+fn key_holder_renounces_their_key() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let key = context.new_owner_custom_key(&["CreateNativeProof"]);
+    let vault = context.key_badge_vault(&key);
+
+    // the owner has no key to renounce
+    expect_error(context.call_with_proof(&owner, "renounce_key", |proof| manifest_args!(vault, proof.unwrap())), AccessManagerError::NotAuthorized);
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(key.account, key.badge, [key.badge_id.clone()])
+        .pop_from_auth_zone("proof")
+        .call_method_with_name_lookup(context.component, "renounce_key", |lookup| (vault, lookup.proof("proof")))
+        .build();
+    context.execute(manifest, &key).expect_commit_success();
+    assert!(context.key_burned_at(&key.badge_id).is_some());
+    // the key is burned, there is nothing left to prove
+    context.call_with_proof(&key, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_failure();
+}
+
+// key transfer
+
+// `transfer_key_badge` for `key`, authorized by `caller` (the key itself or the owner)
+fn transfer_key(context: &mut TestContext, caller: &Caller, key: &Caller, destination: ComponentAddress) -> TransactionReceipt {
+    let vault = context.key_badge_vault(key);
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(caller.account, caller.badge, [caller.badge_id.clone()])
+        .pop_from_auth_zone("proof")
+        .call_method_with_name_lookup(context.component, "transfer_key_badge", |lookup| (vault, key.badge_id.clone(), destination, lookup.proof("proof")))
+        .build();
+    context.execute(manifest, caller)
+}
+
+#[test]
+fn transferable_key_can_be_moved_by_its_holder() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let permissions = vec!["Validator_UpdateFee".to_string()];
+    let key = context.new_key(&owner, "create_custom_access_key_badge_with_policy", |proof| manifest_args!(permissions, KeyTransferPolicy::Transferable, proof));
+    let (public_key, _private_key, new_account) = context.ledger.new_allocated_account();
+
+    transfer_key(&mut context, &key, &key, new_account).expect_commit_success();
+
+    let moved = Caller { public_key, account: new_account, ..key };
+    context.call_with_proof(&moved, "validator_update_fee", |proof| manifest_args!(dec!("0.02"), proof)).expect_commit_success();
+}
+
+#[test]
+fn soulbound_key_can_only_be_moved_by_the_owner() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let key = context.new_owner_custom_key(&["Validator_UpdateFee"]);
+    let (_public_key, _private_key, new_account) = context.ledger.new_allocated_account();
+
+    expect_error(transfer_key(&mut context, &key, &key, new_account), AccessManagerError::KeyNotTransferable);
+    transfer_key(&mut context, &owner, &key, new_account).expect_commit_success();
+}
+
+#[test]
+fn key_holders_cannot_move_other_keys() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let permissions = vec!["CreateNativeProof".to_string()];
+    let key = context.new_key(&owner, "create_custom_access_key_badge_with_policy", |proof| manifest_args!(permissions.clone(), KeyTransferPolicy::Transferable, proof));
+    let other = context.new_key(&owner, "create_custom_access_key_badge_with_policy", |proof| manifest_args!(permissions, KeyTransferPolicy::Transferable, proof));
+
+    expect_error(transfer_key(&mut context, &key, &other, key.account), AccessManagerError::NotAuthorized);
+}
+
+// account-bound keys
+
+// mints a `Validator_UpdateFee` key bound to `bound_account` and deposits it into `holder_account`
+fn new_bound_key(context: &mut TestContext, transfer_policy: KeyTransferPolicy, bound_account: ComponentAddress, holder_account: ComponentAddress, holder_key: Secp256k1PublicKey) -> Caller {
+    let owner = context.owner.clone();
+    let permissions = vec!["Validator_UpdateFee".to_string()];
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(owner.account, owner.badge, [owner.badge_id.clone()])
+        .pop_from_auth_zone("proof")
+        .call_method_with_name_lookup(context.component, "create_bound_access_key_badge", |lookup| (permissions, transfer_policy, bound_account, Some(lookup.proof("proof"))))
+        .create_proof_from_account_of_non_fungibles(owner.account, owner.badge, [owner.badge_id.clone()])
+        .try_deposit_entire_worktop_or_abort(holder_account, None)
+        .build();
+    let receipt = context.execute(manifest, &owner);
+    context.key_holder(receipt, holder_account, holder_key)
+}
+
+#[test]
+fn bound_key_is_only_accepted_from_its_account() {
+    let mut context = TestContext::new();
+    let (public_key, _private_key, account) = context.ledger.new_allocated_account();
+    let (other_public_key, _other_private_key, other_account) = context.ledger.new_allocated_account();
+
+    let key = new_bound_key(&mut context, KeyTransferPolicy::Soulbound, account, account, public_key);
+    context.call_with_proof(&key, "validator_update_fee", |proof| manifest_args!(dec!("0.02"), proof)).expect_commit_success();
+
+    // a key bound to `account` that ended up in another account is useless there
+    let leaked = new_bound_key(&mut context, KeyTransferPolicy::Soulbound, account, other_account, other_public_key);
+    expect_access_rule_failure(context.call_with_proof(&leaked, "validator_update_fee", |proof| manifest_args!(dec!("0.02"), proof)));
+    // the views answer instead of failing, they do not check the bound account
+    assert!(context.can_call(&leaked, "validator_update_fee"));
+}
+
+#[test]
+fn transferred_bound_key_is_rebound_to_its_destination() {
+    let mut context = TestContext::new();
+    let (public_key, _private_key, account) = context.ledger.new_allocated_account();
+    let (new_public_key, _new_private_key, new_account) = context.ledger.new_allocated_account();
+    let key = new_bound_key(&mut context, KeyTransferPolicy::Transferable, account, account, public_key);
+
+    transfer_key(&mut context, &key, &key, new_account).expect_commit_success();
+
+    let moved = Caller { public_key: new_public_key, account: new_account, ..key };
+    context.call_with_proof(&moved, "validator_update_fee", |proof| manifest_args!(dec!("0.02"), proof)).expect_commit_success();
+}
+
+// signature delegates
+
+#[test]
+fn signature_delegate_uses_its_permissions_without_a_badge() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let (bot_key, _bot_private_key) = context.ledger.new_key_pair();
+    let bot = NonFungibleGlobalId::from_public_key(&bot_key);
+    context.call_with_auth_zone(&owner, "register_signature_delegate", manifest_args!(bot.clone(), vec!["Validator_UpdateFee"])).expect_commit_success();
+
+    let delegates: IndexMap<NonFungibleGlobalId, Vec<KeyBadgePermission>> = context.view("get_signature_delegates");
+    assert_eq!(delegates.get(&bot), Some(&vec![KeyBadgePermission::Validator_UpdateFee]));
+
+    context.call_as_signature_delegate(bot_key, "validator_update_fee", manifest_args!(dec!("0.02"), None::<ManifestProof>)).expect_commit_success();
+    // no registered delegate holds Validator_UpdateKey
+    let new_key = Secp256k1PrivateKey::from_u64(99).unwrap().public_key();
+    expect_error(context.call_as_signature_delegate(bot_key, "validator_update_key", manifest_args!(new_key, None::<ManifestProof>)), AccessManagerError::NotAuthorized);
+
+    // other signers are rejected by the auth zone check
+    let (stranger_key, _stranger_private_key) = context.ledger.new_key_pair();
+    expect_access_rule_failure(context.call_as_signature_delegate(stranger_key, "validator_update_fee", manifest_args!(dec!("0.02"), None::<ManifestProof>)));
+
+    context.call_with_auth_zone(&owner, "remove_signature_delegate", manifest_args!(bot)).expect_commit_success();
+    expect_error(context.call_as_signature_delegate(bot_key, "validator_update_fee", manifest_args!(dec!("0.02"), None::<ManifestProof>)), AccessManagerError::NotAuthorized);
+}
+
+#[test]
+fn only_signature_badges_can_be_registered_as_delegates() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let not_a_signature = NonFungibleGlobalId::new(owner.badge, owner.badge_id.clone());
+
+    let receipt = context.call_with_auth_zone(&owner, "register_signature_delegate", manifest_args!(not_a_signature.clone(), vec!["Validator_UpdateFee"]));
+    expect_error(receipt, AccessManagerError::InvalidSignatureDelegate);
+    let receipt = context.call_with_auth_zone(&owner, "remove_signature_delegate", manifest_args!(not_a_signature));
+    expect_error(receipt, AccessManagerError::SignatureDelegateNotRegistered);
+}
+
+#[test]
+fn signature_delegate_cannot_grant_super_permissions() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let (bot_key, _bot_private_key) = context.ledger.new_key_pair();
+    let bot = NonFungibleGlobalId::from_public_key(&bot_key);
+    context.call_with_auth_zone(&owner, "register_signature_delegate", manifest_args!(bot, vec!["CreateAccessKey", "RecallAccessKey"])).expect_commit_success();
+
+    let receipt = context.call_as_signature_delegate(bot_key, "create_custom_access_key_badge", manifest_args!(vec!["RecallAccessKey"], None::<ManifestProof>));
+    expect_error(receipt, AccessManagerError::SuperPermissionForbidden);
+}
+
+// session keys
+
+// `key` derives a session key with `permissions`, uses it for `validator_update_fee`, then hands the session bucket to `finish`
+fn session_key_manifest(context: &mut TestContext, key: &Caller, permissions: Vec<&str>, finish: impl FnOnce(ManifestBuilder) -> ManifestBuilder) -> TransactionManifestV1 {
+    let session_key_badge = context.view::<ResourceAddresses>("get_resource_addresses").session_key_badge;
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(key.account, key.badge, [key.badge_id.clone()])
+        .pop_from_auth_zone("parent")
+        .call_method_with_name_lookup(context.component, "create_session_key", |lookup| (permissions, lookup.proof("parent")))
+        .take_all_from_worktop(session_key_badge, "session")
+        .create_proof_from_bucket_of_all("session", "session_proof")
+        .call_method_with_name_lookup(context.component, "validator_update_fee", |lookup| (dec!("0.02"), Some(lookup.proof("session_proof"))));
+    finish(manifest).build()
+}
+
+#[test]
+fn session_key_is_usable_until_burned_in_the_same_transaction() {
+    let mut context = TestContext::new();
+    let key = context.new_owner_custom_key(&["Validator_UpdateFee", "CreateNativeProof"]);
+    let component = context.component;
+
+    let manifest = session_key_manifest(&mut context, &key, vec!["Validator_UpdateFee"], |builder| {
+        builder
+            // `burn_key_badge` is restricted to the owner and key holder roles
+            .create_proof_from_account_of_non_fungibles(key.account, key.badge, [key.badge_id.clone()])
+            .call_method_with_name_lookup(component, "burn_key_badge", |lookup| (lookup.bucket("session"),))
+    });
+    context.execute(manifest, &key).expect_commit_success();
+}
+
+#[test]
+fn session_key_cannot_leave_the_transaction() {
+    let mut context = TestContext::new();
+    let key = context.new_owner_custom_key(&["Validator_UpdateFee"]);
+    let account = key.account;
+
+    let manifest = session_key_manifest(&mut context, &key, vec!["Validator_UpdateFee"], |builder| builder.deposit(account, "session"));
+    context.execute(manifest, &key).expect_commit_failure();
+}
+
+#[test]
+fn session_key_only_carries_permissions_of_its_parent() {
+    let mut context = TestContext::new();
+    let key = context.new_owner_custom_key(&["CreateNativeProof"]);
+
+    let manifest = session_key_manifest(&mut context, &key, vec!["Validator_UpdateFee"], |builder| builder);
+    let receipt = context.execute(manifest, &key);
+    assert_eq!(panic_error(&receipt), Some(AccessManagerError::MissingGrantedPermission));
+}
+
+#[test]
+fn session_keys_never_carry_super_permissions() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+
+    for permission in ["CreateAccessKey", "RecallAccessKey"] {
+        let manifest = session_key_manifest(&mut context, &owner, vec![permission], |builder| builder);
+        let receipt = context.execute(manifest, &owner);
+        assert_eq!(panic_error(&receipt), Some(AccessManagerError::SuperPermissionForbidden));
+    }
+}
+
+// native proof
+
+#[test]
+fn create_auth_badge_proof_requires_create_native_proof() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let native_key = context.new_owner_custom_key(&["CreateNativeProof"]);
+    let fee_key = context.new_owner_custom_key(&["Validator_UpdateFee"]);
+
+    context.call_with_proof(&owner, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_success();
+    context.call_with_proof(&native_key, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_success();
+    expect_error(context.call_with_proof(&fee_key, "create_auth_badge_proof", |proof| manifest_args!(proof)), AccessManagerError::NotAuthorized);
+}
+
+// validator extension
+
+#[test]
+fn validator_update_fee_requires_update_fee_permission() {
+    let mut context = TestContext::new();
+    let fee_key = context.new_owner_custom_key(&["Validator_UpdateFee"]);
+    let key_key = context.new_owner_custom_key(&["Validator_UpdateKey"]);
+
+    context.call_with_proof(&fee_key, "validator_update_fee", |proof| manifest_args!(dec!("0.02"), proof)).expect_commit_success();
+    expect_error(context.call_with_proof(&key_key, "validator_update_fee", |proof| manifest_args!(dec!("0.02"), proof)), AccessManagerError::NotAuthorized);
+}
+
+#[test]
+fn super_key_with_validator_permissions_can_manage_the_validator() {
+    let mut context = TestContext::new();
+    let super_key = context.new_super_key();
+    let new_key = Secp256k1PrivateKey::from_u64(99).unwrap().public_key();
+
+    context.call_with_proof(&super_key, "validator_register", |proof| manifest_args!(proof)).expect_commit_success();
+    context.call_with_proof(&super_key, "validator_unregister", |proof| manifest_args!(proof)).expect_commit_success();
+    context.call_with_proof(&super_key, "validator_update_key", |proof| manifest_args!(new_key, proof)).expect_commit_success();
+    context.call_with_proof(&super_key, "validator_update_fee", |proof| manifest_args!(dec!("0.01"), proof)).expect_commit_success();
+    context.call_with_proof(&super_key, "validator_update_accept_delegated_stake", |proof| manifest_args!(false, proof)).expect_commit_success();
+    context.call_with_proof(&super_key, "validator_signal_protocol_update_readiness", |proof| manifest_args!("a".repeat(32), proof)).expect_commit_success();
+}
+
+#[test]
+fn super_key_without_validator_permissions_cannot_manage_the_validator() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let (public_key, _private_key, account) = context.ledger.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(owner.account, owner.badge, [owner.badge_id.clone()])
+        .call_method(context.component, "create_super_access_key_badge", manifest_args!(false))
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    let receipt = context.execute(manifest, &owner);
+    let super_key = context.key_holder(receipt, account, public_key);
+
+    expect_error(context.call_with_proof(&super_key, "validator_register", |proof| manifest_args!(proof)), AccessManagerError::NotAuthorized);
+}
+
+#[test]
+fn scoped_metadata_permission_only_allows_its_own_key() {
+    let mut context = TestContext::new();
+    let name_key = context.new_owner_custom_key(&["Validator_SetMetadata_Name"]);
+
+    context.call_with_proof(&name_key, "validator_set_metadata", |proof| manifest_args!("name", "Marketing Name", proof)).expect_commit_success();
+    expect_error(context.call_with_proof(&name_key, "validator_set_metadata", |proof| manifest_args!("description", "Not allowed", proof)), AccessManagerError::NotAuthorized);
+}
+
+#[test]
+fn metadata_permissions_are_only_granted_through_custom_keys() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let super_key = context.new_super_key();
+    let basic_key = context.new_key(&owner, "create_basic_key_badge", |proof| manifest_args!(true, proof));
+
+    for key in [&super_key, &basic_key] {
+        expect_error(context.call_with_proof(key, "validator_set_metadata", |proof| manifest_args!("name", "Access Managed Validator", proof)), AccessManagerError::NotAuthorized);
+        expect_error(context.call_with_proof(key, "validator_lock_metadata", |proof| manifest_args!("name", proof)), AccessManagerError::NotAuthorized);
+        expect_error(context.call_with_proof(key, "validator_set_metadata_role", |proof| manifest_args!("metadata_setter", rule!(deny_all), proof)), AccessManagerError::NotAuthorized);
+    }
+
+    let metadata_key = context.new_owner_custom_key(&["Validator_SetMetadata", "Validator_LockMetadata", "Validator_SetMetadataRole"]);
+    context.call_with_proof(&metadata_key, "validator_set_metadata", |proof| manifest_args!("name", "Access Managed Validator", proof)).expect_commit_success();
+    context.call_with_proof(&metadata_key, "validator_set_metadata", |proof| manifest_args!("info_url", "https://example.com", proof)).expect_commit_success();
+    context.call_with_proof(&metadata_key, "validator_lock_metadata", |proof| manifest_args!("name", proof)).expect_commit_success();
+    context.call_with_proof(&metadata_key, "validator_set_metadata_role", |proof| manifest_args!("metadata_setter", rule!(deny_all), proof)).expect_commit_success();
+}
+
+#[test]
+fn stake_lock_and_unlock_owner_stake_units() {
+    let mut context = TestContext::new();
+    let super_key = context.new_super_key();
+
+    // stake as owner, the stake units are returned to the key holder and locked again
+    context.call_with_bucket_and_proof(&super_key, XRD, dec!(100), "validator_stake_as_owner").expect_commit_success();
+    let stake_unit = context.stake_unit();
+    context.call_with_bucket_and_proof(&super_key, stake_unit, dec!(100), "validator_lock_owner_stake_units").expect_commit_success();
+
+    context.call_with_proof(&super_key, "validator_start_unlock_owner_stake_units", |proof| manifest_args!(dec!(50), proof)).expect_commit_success();
+    context.call_with_proof(&super_key, "validator_finish_unlock_owner_stake_units", |proof| manifest_args!(proof)).expect_commit_success();
+}
+
+#[test]
+fn payout_methods_require_a_payout_account() {
+    let mut context = TestContext::new();
+    let trigger_key = context.new_owner_custom_key(&["Validator_FinishUnlockOwnerStakeUnitsToPayout"]);
+
+    let receipt = context.call_with_proof(&trigger_key, "validator_finish_unlock_owner_stake_units_to_payout", |proof| manifest_args!(proof));
+    expect_error(receipt, AccessManagerError::PayoutAccountNotConfigured);
+}
+
+#[test]
+fn stake_as_owner_to_payout_deposits_into_the_payout_account() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let trigger_key = context.new_owner_custom_key(&["Validator_StakeAsOwnerToPayout"]);
+    context.call_with_auth_zone(&owner, "set_payout_account", manifest_args!(Some(owner.account))).expect_commit_success();
+
+    context.call_with_bucket_and_proof(&trigger_key, XRD, dec!(100), "validator_stake_as_owner_to_payout").expect_commit_success();
+
+    let stake_unit = context.stake_unit();
+    assert!(context.ledger.get_component_balance(owner.account, stake_unit).is_positive());
+    assert!(context.ledger.get_component_balance(trigger_key.account, stake_unit).is_zero());
+}
+
+#[test]
+fn treasury_stake_and_lock() {
+    let mut context = TestContext::new();
+    let treasurer = context.new_owner_custom_key(&["Treasury_Deposit", "Treasury_Withdraw"]);
+    let operator = context.new_owner_custom_key(&["Validator_StakeFromTreasury", "Validator_LockOwnerStakeUnitsFromTreasury"]);
+
+    context.call_with_bucket_and_proof(&treasurer, XRD, dec!(200), "deposit_to_treasury").expect_commit_success();
+
+    context.call_with_proof(&operator, "validator_stake_from_treasury", |proof| manifest_args!(dec!(100), proof)).expect_commit_success();
+    context.call_with_proof(&operator, "validator_lock_owner_stake_units_from_treasury", |proof| manifest_args!(dec!(100), proof)).expect_commit_success();
+    context.call_with_proof(&treasurer, "withdraw_from_treasury", |proof| manifest_args!(XRD, dec!(100), proof)).expect_commit_success();
+    expect_error(context.call_with_proof(&operator, "withdraw_from_treasury", |proof| manifest_args!(XRD, dec!(1), proof)), AccessManagerError::NotAuthorized);
+}
+
+#[test]
+fn compound_owner_stake_fails_without_unlocked_stake() {
+    let mut context = TestContext::new();
+    let compound_key = context.new_owner_custom_key(&["Validator_CompoundOwnerStake"]);
+
+    let receipt = context.call_with_proof(&compound_key, "validator_compound_owner_stake", |proof| manifest_args!(proof));
+    expect_error(receipt, AccessManagerError::NothingToCompound);
+}
+
+#[test]
+fn compound_owner_stake_locks_the_unlocked_stake_units_again() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let compound_key = context.new_owner_custom_key(&["Validator_CompoundOwnerStake"]);
+    context.call_with_bucket_and_proof(&owner, XRD, dec!(1000), "validator_stake_as_owner").expect_commit_success();
+    let stake_unit = context.stake_unit();
+    context.call_with_bucket_and_proof(&owner, stake_unit, dec!(500), "validator_lock_owner_stake_units").expect_commit_success();
+    context.call_with_proof(&owner, "validator_start_unlock_owner_stake_units", |proof| manifest_args!(dec!(100), proof)).expect_commit_success();
+    let matured = Epoch::of(context.ledger.get_current_epoch().number() + 100_000);
+    context.ledger.set_current_epoch(matured);
+
+    context.call_with_proof(&compound_key, "validator_compound_owner_stake", |proof| manifest_args!(proof)).expect_commit_success();
+    let locked_vault = context.ledger.get_validator_info(context.validator).locked_owner_stake_unit_vault_id;
+    assert_eq!(context.ledger.inspect_vault_balance(locked_vault.0), Some(dec!(500)));
+}
+
+// rate limits
+
+fn set_rate_limit(context: &mut TestContext, permission: &str, rate_limit: Option<RateLimit>) {
+    let owner = context.owner.clone();
+    context.call_with_auth_zone(&owner, "set_rate_limit", manifest_args!(permission.to_string(), rate_limit)).expect_commit_success();
+}
+
+#[test]
+fn rate_limit_resets_with_the_next_window() {
+    let mut context = TestContext::new();
+    let key = context.new_owner_custom_key(&["Validator_UpdateFee"]);
+    set_rate_limit(&mut context, "Validator_UpdateFee", Some(RateLimit { max_calls: 1, window: RateLimitWindow::Epochs(1) }));
+
+    context.call_with_proof(&key, "validator_update_fee", |proof| manifest_args!(dec!("0.02"), proof)).expect_commit_success();
+    let receipt = context.call_with_proof(&key, "validator_update_fee", |proof| manifest_args!(dec!("0.03"), proof));
+    expect_error(receipt, AccessManagerError::RateLimitExceeded);
+
+    let next_epoch = Epoch::of(context.ledger.get_current_epoch().number() + 1);
+    context.ledger.set_current_epoch(next_epoch);
+    context.call_with_proof(&key, "validator_update_fee", |proof| manifest_args!(dec!("0.03"), proof)).expect_commit_success();
+}
+
+#[test]
+fn key_rate_limit_overrides_the_component_limit() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let trusted = context.new_owner_custom_key(&["CreateNativeProof"]);
+    let other = context.new_owner_custom_key(&["CreateNativeProof"]);
+    set_rate_limit(&mut context, "CreateNativeProof", Some(RateLimit { max_calls: 1, window: RateLimitWindow::Seconds(86400) }));
+    let trusted_limit = RateLimit { max_calls: 2, window: RateLimitWindow::Seconds(86400) };
+    context
+        .call_with_auth_zone(&owner, "set_key_rate_limit", manifest_args!(trusted.badge_id.clone(), "CreateNativeProof".to_string(), Some(trusted_limit)))
+        .expect_commit_success();
+
+    for _ in 0..2 {
+        context.call_with_proof(&trusted, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_success();
+    }
+    expect_error(context.call_with_proof(&trusted, "create_auth_badge_proof", |proof| manifest_args!(proof)), AccessManagerError::RateLimitExceeded);
+
+    context.call_with_proof(&other, "create_auth_badge_proof", |proof| manifest_args!(proof)).expect_commit_success();
+    expect_error(context.call_with_proof(&other, "create_auth_badge_proof", |proof| manifest_args!(proof)), AccessManagerError::RateLimitExceeded);
+}
+
+#[test]
+fn session_keys_cannot_carry_rate_limited_permissions() {
+    let mut context = TestContext::new();
+    let key = context.new_owner_custom_key(&["Validator_UpdateFee"]);
+    set_rate_limit(&mut context, "Validator_UpdateFee", Some(RateLimit { max_calls: 1, window: RateLimitWindow::Epochs(1) }));
+
+    let manifest = session_key_manifest(&mut context, &key, vec!["Validator_UpdateFee"], |builder| builder);
+    expect_error(context.execute(manifest, &key), AccessManagerError::RateLimitedSessionPermission);
+}
+
+#[test]
+fn rate_limited_permissions_cannot_be_granted_to_new_keys() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let key = context.new_owner_custom_key(&["CreateAccessKey", "Validator_UpdateFee"]);
+    let (bot_key, _bot_private_key) = context.ledger.new_key_pair();
+    let bot = NonFungibleGlobalId::from_public_key(&bot_key);
+    context.call_with_auth_zone(&owner, "register_signature_delegate", manifest_args!(bot, vec!["CreateAccessKey", "Validator_UpdateFee"])).expect_commit_success();
+    set_rate_limit(&mut context, "Validator_UpdateFee", Some(RateLimit { max_calls: 1, window: RateLimitWindow::Epochs(1) }));
+
+    let receipt = context.call_with_proof(&key, "create_custom_access_key_badge", |proof| manifest_args!(vec!["Validator_UpdateFee"], proof));
+    expect_error(receipt, AccessManagerError::RateLimitedGrantedPermission);
+    let receipt = context.call_as_signature_delegate(bot_key, "create_custom_access_key_badge", manifest_args!(vec!["Validator_UpdateFee"], None::<ManifestProof>));
+    expect_error(receipt, AccessManagerError::NotAuthorized);
+    context.call_with_proof(&owner, "create_custom_access_key_badge", |proof| manifest_args!(vec!["Validator_UpdateFee"], proof)).expect_commit_success();
+}
+
+#[test]
+fn can_call_is_false_once_the_rate_limit_is_used_up() {
+    let mut context = TestContext::new();
+    let key = context.new_owner_custom_key(&["Validator_UpdateFee"]);
+    set_rate_limit(&mut context, "Validator_UpdateFee", Some(RateLimit { max_calls: 1, window: RateLimitWindow::Epochs(1) }));
+
+    assert!(context.can_call(&key, "validator_update_fee"));
+    context.call_with_proof(&key, "validator_update_fee", |proof| manifest_args!(dec!("0.02"), proof)).expect_commit_success();
+    assert!(!context.can_call(&key, "validator_update_fee"));
+}
+
+#[test]
+fn rate_limit_window_cannot_be_empty() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+
+    let rate_limit = Some(RateLimit { max_calls: 1, window: RateLimitWindow::Epochs(0) });
+    let receipt = context.call_with_auth_zone(&owner, "set_rate_limit", manifest_args!("Validator_UpdateFee".to_string(), rate_limit));
+    expect_error(receipt, AccessManagerError::InvalidRateLimit);
+}
+
+#[test]
+fn signature_delegates_have_their_own_rate_limit() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let (trusted_key, _trusted_private_key) = context.ledger.new_key_pair();
+    let (other_key, _other_private_key) = context.ledger.new_key_pair();
+    let trusted = NonFungibleGlobalId::from_public_key(&trusted_key);
+    for delegate in [&trusted, &NonFungibleGlobalId::from_public_key(&other_key)] {
+        context.call_with_auth_zone(&owner, "register_signature_delegate", manifest_args!(delegate.clone(), vec!["Validator_UpdateFee"])).expect_commit_success();
+    }
+    set_rate_limit(&mut context, "Validator_UpdateFee", Some(RateLimit { max_calls: 1, window: RateLimitWindow::Epochs(1) }));
+    let trusted_limit = RateLimit { max_calls: 2, window: RateLimitWindow::Epochs(1) };
+    context
+        .call_with_auth_zone(&owner, "set_delegate_rate_limit", manifest_args!(trusted.clone(), "Validator_UpdateFee".to_string(), Some(trusted_limit)))
+        .expect_commit_success();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(context.component, "get_delegate_rate_limit", manifest_args!(trusted, "Validator_UpdateFee".to_string()))
+        .build();
+    let limit: Option<RateLimit> = context.ledger.execute_manifest(manifest, vec![]).expect_commit_success().output(1);
+    assert_eq!(limit, Some(trusted_limit));
+
+    // the signer is not known, so the use counts for both delegates holding the permission
+    context.call_as_signature_delegate(other_key, "validator_update_fee", manifest_args!(dec!("0.02"), None::<ManifestProof>)).expect_commit_success();
+    // the other delegate used up its limit and is no longer accepted, the trusted one has a use left
+    expect_access_rule_failure(context.call_as_signature_delegate(other_key, "validator_update_fee", manifest_args!(dec!("0.03"), None::<ManifestProof>)));
+    context.call_as_signature_delegate(trusted_key, "validator_update_fee", manifest_args!(dec!("0.03"), None::<ManifestProof>)).expect_commit_success();
+    let receipt = context.call_as_signature_delegate(trusted_key, "validator_update_fee", manifest_args!(dec!("0.04"), None::<ManifestProof>));
+    expect_error(receipt, AccessManagerError::RateLimitExceeded);
+}
+
+#[test]
+fn delegate_rate_limit_needs_a_registered_delegate() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let (bot_key, _bot_private_key) = context.ledger.new_key_pair();
+
+    let rate_limit = Some(RateLimit { max_calls: 1, window: RateLimitWindow::Epochs(1) });
+    let args = manifest_args!(NonFungibleGlobalId::from_public_key(&bot_key), "Validator_UpdateFee".to_string(), rate_limit);
+    expect_error(context.call_with_auth_zone(&owner, "set_delegate_rate_limit", args), AccessManagerError::SignatureDelegateNotRegistered);
+}
+
+#[test]
+fn scoped_rate_limit_holds_next_to_the_broad_permission() {
+    let mut context = TestContext::new();
+    let key = context.new_owner_custom_key(&["Validator_SetMetadata", "Validator_SetMetadata_Name"]);
+    set_rate_limit(&mut context, "Validator_SetMetadata_Name", Some(RateLimit { max_calls: 1, window: RateLimitWindow::Epochs(1) }));
+
+    context.call_with_proof(&key, "validator_set_metadata", |proof| manifest_args!("name", "Access Managed Validator", proof)).expect_commit_success();
+    let receipt = context.call_with_proof(&key, "validator_set_metadata", |proof| manifest_args!("name", "Renamed Validator", proof));
+    expect_error(receipt, AccessManagerError::RateLimitExceeded);
+}
+
+// delayed validator operations
+
+fn delay_update_key(context: &mut TestContext, delay_seconds: i64) -> Caller {
+    let owner = context.owner.clone();
+    context.call_with_auth_zone(&owner, "set_validator_operation_delay", manifest_args!("validator_update_key".to_string(), Some(delay_seconds))).expect_commit_success();
+    context.new_owner_custom_key(&["Validator_UpdateKey"])
+}
+
+fn propose_update_key(context: &mut TestContext, key: &Caller) -> u64 {
+    let new_key = Secp256k1PrivateKey::from_u64(99).unwrap().public_key();
+    let receipt = context.call_with_proof(key, "propose_validator_operation", |proof| manifest_args!(ValidatorOperation::UpdateKey(new_key), proof));
+    // the call follows the fee lock, the account proof and popping it
+    receipt.expect_commit_success().output(3)
+}
+
+#[test]
+fn delayed_operation_is_executable_after_its_delay() {
+    let mut context = TestContext::new();
+    let key = delay_update_key(&mut context, 3600);
+    let new_key = Secp256k1PrivateKey::from_u64(99).unwrap().public_key();
+
+    let receipt = context.call_with_proof(&key, "validator_update_key", |proof| manifest_args!(new_key, proof));
+    expect_error(receipt, AccessManagerError::ProposalRequired);
+
+    let proposal_id = propose_update_key(&mut context, &key);
+    let proposals: IndexMap<u64, ValidatorProposal> = context.view("get_validator_proposals");
+    assert_eq!(proposals[&proposal_id].proposer, Proposer::AccessKey(key.badge_id.clone()));
+
+    let owner = context.owner.clone();
+    let receipt = context.call_with_auth_zone(&owner, "execute_validator_proposal", manifest_args!(proposal_id));
+    expect_error(receipt, AccessManagerError::ProposalNotExecutable);
+
+    context.advance_time(3600);
+    context.call_with_auth_zone(&owner, "execute_validator_proposal", manifest_args!(proposal_id)).expect_commit_success();
+    assert!(context.view::<IndexMap<u64, ValidatorProposal>>("get_validator_proposals").is_empty());
+}
+
+#[test]
+fn owner_can_still_call_a_delayed_operation_directly() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    delay_update_key(&mut context, 3600);
+    let new_key = Secp256k1PrivateKey::from_u64(99).unwrap().public_key();
+
+    context.call_with_proof(&owner, "validator_update_key", |proof| manifest_args!(new_key, proof)).expect_commit_success();
+}
+
+#[test]
+fn guardian_vetoes_a_proposal() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let key = delay_update_key(&mut context, 3600);
+    let (guardian, _private_key, _account) = context.ledger.new_allocated_account();
+    context.call_with_auth_zone(&owner, "register_guardian", manifest_args!(NonFungibleGlobalId::from_public_key(&guardian))).expect_commit_success();
+    let proposal_id = propose_update_key(&mut context, &key);
+
+    // the key holder is no guardian
+    let receipt = context.call_with_auth_zone(&key, "veto_validator_proposal", manifest_args!(proposal_id, None::<ManifestProof>));
+    expect_access_rule_failure(receipt);
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(context.component, "veto_validator_proposal", manifest_args!(proposal_id, None::<ManifestProof>))
+        .build();
+    context.ledger.execute_manifest(manifest, vec![NonFungibleGlobalId::from_public_key(&guardian)]).expect_commit_success();
+
+    context.advance_time(3600);
+    let receipt = context.call_with_auth_zone(&owner, "execute_validator_proposal", manifest_args!(proposal_id));
+    expect_error(receipt, AccessManagerError::ProposalNotFound);
+}
+
+#[test]
+fn owner_vetoes_a_proposal_with_the_owner_badge() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let key = delay_update_key(&mut context, 3600);
+    let proposal_id = propose_update_key(&mut context, &key);
+
+    // a key holder cannot veto with their key
+    let receipt = context.call_with_proof(&key, "veto_validator_proposal", |proof| manifest_args!(proposal_id, proof));
+    expect_error(receipt, AccessManagerError::NotAuthorized);
+
+    context.call_with_proof(&owner, "veto_validator_proposal", |proof| manifest_args!(proposal_id, proof)).expect_commit_success();
+    assert!(context.view::<IndexMap<u64, ValidatorProposal>>("get_validator_proposals").is_empty());
+}
+
+#[test]
+fn only_update_key_and_unregister_can_be_delayed() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+
+    let receipt = context.call_with_auth_zone(&owner, "set_validator_operation_delay", manifest_args!("validator_update_fee".to_string(), Some(3600i64)));
+    expect_error(receipt, AccessManagerError::InvalidProposalDelay);
+}
+
+#[test]
+fn delay_is_bounded() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+
+    let too_long = MAX_VALIDATOR_OPERATION_DELAY_SECONDS + 1;
+    let receipt = context.call_with_auth_zone(&owner, "set_validator_operation_delay", manifest_args!("validator_update_key".to_string(), Some(too_long)));
+    expect_error(receipt, AccessManagerError::ProposalDelayTooLong);
+}
+
+#[test]
+fn delay_set_later_holds_back_queued_proposals() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let key = delay_update_key(&mut context, 0);
+    let proposal_id = propose_update_key(&mut context, &key);
+
+    context.call_with_auth_zone(&owner, "set_validator_operation_delay", manifest_args!("validator_update_key".to_string(), Some(3600i64))).expect_commit_success();
+    let receipt = context.call_with_auth_zone(&owner, "execute_validator_proposal", manifest_args!(proposal_id));
+    expect_error(receipt, AccessManagerError::ProposalNotExecutable);
+
+    context.advance_time(3600);
+    context.call_with_auth_zone(&owner, "execute_validator_proposal", manifest_args!(proposal_id)).expect_commit_success();
+}
+
+#[test]
+fn recalling_the_key_drops_its_proposals() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let key = delay_update_key(&mut context, 3600);
+    let proposal_id = propose_update_key(&mut context, &key);
+
+    let vault = context.key_badge_vault(&key);
+    context.call_with_proof(&owner, "recall_key_badges_by_id", |proof| manifest_args!(vault, vec![key.badge_id.clone()], proof)).expect_commit_success();
+    assert!(context.view::<IndexMap<u64, ValidatorProposal>>("get_validator_proposals").is_empty());
+
+    context.advance_time(3600);
+    let receipt = context.call_with_auth_zone(&owner, "execute_validator_proposal", manifest_args!(proposal_id));
+    expect_error(receipt, AccessManagerError::ProposalNotFound);
+}
+
+#[test]
+fn proposal_of_a_key_that_renounced_the_permission_cannot_execute() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let key = delay_update_key(&mut context, 3600);
+    let proposal_id = propose_update_key(&mut context, &key);
+
+    context.call_with_proof(&key, "renounce_permissions", |proof| manifest_args!(vec!["Validator_UpdateKey"], proof.unwrap())).expect_commit_success();
+    context.advance_time(3600);
+    let receipt = context.call_with_auth_zone(&owner, "execute_validator_proposal", manifest_args!(proposal_id));
+    expect_error(receipt, AccessManagerError::ProposerNotAuthorized);
+}
+
+#[test]
+fn removing_a_signature_delegate_drops_its_proposals() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    delay_update_key(&mut context, 3600);
+    let (bot_key, _bot_private_key) = context.ledger.new_key_pair();
+    let bot = NonFungibleGlobalId::from_public_key(&bot_key);
+    context.call_with_auth_zone(&owner, "register_signature_delegate", manifest_args!(bot.clone(), vec!["Validator_UpdateKey"])).expect_commit_success();
+
+    let new_key = Secp256k1PrivateKey::from_u64(99).unwrap().public_key();
+    let receipt = context.call_as_signature_delegate(bot_key, "propose_validator_operation", manifest_args!(ValidatorOperation::UpdateKey(new_key), None::<ManifestProof>));
+    let proposal_id: u64 = receipt.expect_commit_success().output(1);
+    let proposals: IndexMap<u64, ValidatorProposal> = context.view("get_validator_proposals");
+    assert_eq!(proposals[&proposal_id].proposer, Proposer::SignatureDelegate(vec![bot.clone()]));
+
+    context.call_with_auth_zone(&owner, "remove_signature_delegate", manifest_args!(bot)).expect_commit_success();
+    assert!(context.view::<IndexMap<u64, ValidatorProposal>>("get_validator_proposals").is_empty());
+}
+
+#[test]
+fn can_call_is_false_for_delayed_methods_without_the_owner_badge() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let key = context.new_owner_custom_key(&["Validator_UpdateKey"]);
+    assert!(context.can_call(&key, "validator_update_key"));
+
+    delay_update_key(&mut context, 3600);
+    assert!(!context.can_call(&key, "validator_update_key"));
+    assert!(context.can_call(&key, "propose_validator_operation"));
+    assert!(context.can_call(&owner, "validator_update_key"));
+}
+
+// error codes
+
+#[test]
+fn error_codes_are_unique_and_parse_back() {
+    let mut codes = Vec::new();
+    for error in AccessManagerError::iter() {
+        assert!(!codes.contains(&error.code()), "{} reuses code {}", error, error.code());
+        codes.push(error.code());
+        let message = format!("Panicked at 'src/lib.rs:1': {} {}", error.tag(), error.message());
+        assert_eq!(AccessManagerError::parse(&message), Some(error));
+    }
+}
+
+#[test]
+fn missing_granted_permission_names_the_permission() {
+    let mut context = TestContext::new();
+    let creator = context.new_owner_custom_key(&["CreateAccessKey"]);
+
+    let receipt = context.call_with_proof(&creator, "create_custom_access_key_badge", |proof| manifest_args!(vec!["Validator_UpdateFee".to_string()], proof));
+    let message = panic_message(&receipt).expect("the call should panic");
+    assert!(message.contains("[AM-102 MissingGrantedPermission] Key holder must have the permissions they are granting: Validator_UpdateFee"), "{}", message);
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use accessmanager::access_manager::custom_types::{AccessManagerConfig, KeyBadgePermission, ResourceAddresses};
+use accessmanager::client::manifest_builder::{AccessManagerManifestBuilder, BadgeHolder};
+use common::*;
+use scrypto_test::prelude::*;
+
+// Each test chains the `am_*` helpers of one group into a manifest and runs it against `TestContext`
+
+fn holder(caller: &Caller) -> BadgeHolder {
+    BadgeHolder { account: caller.account, badge: caller.badge, id: caller.badge_id.clone() }
+}
+
+#[test]
+fn owner_helpers_build_working_manifests() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let (_public_key, _private_key, payout_account) = context.ledger.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .am_set_payout_account(context.component, Some(payout_account), &holder(&owner))
+        .am_withdraw_auth_badge(context.component, &holder(&owner))
+        .build();
+    context.execute(manifest, &owner).expect_commit_success();
+    let config: AccessManagerConfig = context.view("get_config");
+    assert_eq!(config.payout_account, Some(payout_account));
+    assert!(!config.auth_badge.deposited);
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .am_deposit_auth_badge(context.component, &holder(&owner), VALIDATOR_OWNER_BADGE, context.validator_badge_id.clone())
+        .am_create_super_key(context.component, true, &holder(&owner), owner.account)
+        .build();
+    context.execute(manifest, &owner).expect_commit_success();
+    let config: AccessManagerConfig = context.view("get_config");
+    assert!(config.auth_badge.deposited);
+    assert_eq!(context.ledger.get_component_balance(owner.account, context.key_badge), dec!(1));
+}
+
+#[test]
+fn factory_helper_moves_the_auth_badge_to_a_new_manager() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(context.package, "AccessManagerFactory", "instantiate", manifest_args!(false, owner.account))
+        .build();
+    let factory = context.execute(manifest, &owner).expect_commit_success().new_component_addresses()[0];
+
+    // withdrawing from the old manager and registering with the factory fit in one manifest
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .am_withdraw_auth_badge(context.component, &holder(&owner))
+        .am_factory_new_manager(factory, owner.account, VALIDATOR_OWNER_BADGE, context.validator_badge_id.clone(), owner.account)
+        .build();
+    let manager = context.execute(manifest, &owner).expect_commit_success().new_component_addresses()[0];
+
+    let auth_badge = NonFungibleGlobalId::new(VALIDATOR_OWNER_BADGE, context.validator_badge_id.clone());
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(factory, "get_manager_by_auth_badge", manifest_args!(auth_badge))
+        .build();
+    let registered: Option<ComponentAddress> = context.ledger.execute_manifest(manifest, vec![]).expect_commit_success().output(1);
+    assert_eq!(registered, Some(manager));
+}
+
+#[test]
+fn key_helpers_build_working_manifests() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let (public_key, _private_key, account) = context.ledger.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .am_create_custom_key(context.component, &[KeyBadgePermission::Validator_UpdateFee, KeyBadgePermission::CreateNativeProof], &holder(&owner), account)
+        .build();
+    let receipt = context.execute(manifest, &owner);
+    let key = context.key_holder(receipt, account, public_key);
+
+    // a session key is minted and burned again within the manifest that needs it
+    let resources: ResourceAddresses = context.view("get_resource_addresses");
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .am_create_session_key(context.component, resources.session_key_badge, &[KeyBadgePermission::Validator_UpdateFee], &holder(&key), "session_key")
+        .am_burn_session_key(context.component, "session_key", &holder(&key))
+        .build();
+    context.execute(manifest, &key).expect_commit_success();
+
+    let vault = context.key_badge_vault(&key);
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .am_recall_and_burn_key(context.component, context.key_badge, vault, &holder(&owner))
+        .build();
+    context.execute(manifest, &owner).expect_commit_success();
+    assert_eq!(context.ledger.get_component_balance(key.account, context.key_badge), Decimal::ZERO);
+}
+
+#[test]
+fn treasury_helpers_build_working_manifests() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .am_deposit_to_treasury(context.component, XRD, dec!(100), &holder(&owner))
+        .am_withdraw_from_treasury(context.component, XRD, dec!(40), &holder(&owner))
+        .build();
+    context.execute(manifest, &owner).expect_commit_success();
+    let config: AccessManagerConfig = context.view("get_config");
+    assert_eq!(config.xrd_treasury_amount, dec!(60));
+}
+
+#[test]
+fn view_helpers_build_working_manifests() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .am_get_config(context.component)
+        .am_can_call(context.component, "withdraw_auth_badge", &holder(&owner))
+        .build();
+    let receipt = context.execute(manifest, &owner);
+    let commit = receipt.expect_commit_success();
+    let config: AccessManagerConfig = commit.output(1);
+    assert_eq!(config.validator, Some(context.validator));
+    // the proof takes two instructions, `can_call` is the fourth
+    let can_call: bool = commit.output(4);
+    assert!(can_call);
+}
+
+#[test]
+fn validator_helpers_build_working_manifests() {
+    let mut context = TestContext::new();
+    let owner = context.owner.clone();
+    let stake_unit = context.stake_unit();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .am_validator_update_fee(context.component, dec!("0.02"), &holder(&owner))
+        .am_validator_stake_as_owner(context.component, dec!(100), &holder(&owner))
+        .build();
+    context.execute(manifest, &owner).expect_commit_success();
+    assert!(context.ledger.get_component_balance(owner.account, stake_unit) > Decimal::ZERO);
+}
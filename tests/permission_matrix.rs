@@ -0,0 +1,215 @@
+mod common;
+
+use accessmanager::access_manager::access_manager_helper::permission_gated_methods;
+use accessmanager::access_manager::custom_types::{KeyBadgePermission, KeyTransferPolicy, ValidatorOperation};
+use accessmanager::access_manager::errors::AccessManagerError;
+use common::*;
+use scrypto_test::prelude::*;
+use strum::IntoEnumIterator;
+
+// What a key holding one of `granted_by` should see when calling the operation
+enum WhenGranted {
+    Succeeds,
+    // the permission check passes but the call is rejected for another documented reason
+    Panics(AccessManagerError),
+}
+
+// builds the manifest of an operation for a caller
+type OperationManifest = Box<dyn Fn(&MatrixContext, &Caller) -> TransactionManifestV1>;
+
+// One call to a PUBLIC `AccessManager` method, with the permissions that allow it
+struct Operation {
+    name: &'static str,
+    granted_by: Vec<KeyBadgePermission>,
+    when_granted: WhenGranted,
+    manifest: OperationManifest,
+}
+
+// A manager with everything in place for every operation to succeed when permitted:
+// stake units held by the owner, locked and matured unlocking owner stake, a funded treasury, a payout account and keys to recall.
+struct MatrixContext {
+    context: TestContext,
+    stake_unit: ResourceAddress,
+    recall_target: InternalAddress,
+    // separate keys, `recall_key_badge` empties the vault of `recall_target`
+    recall_by_id_target: (InternalAddress, NonFungibleLocalId),
+    recall_and_burn_target: (InternalAddress, NonFungibleLocalId),
+}
+
+impl MatrixContext {
+    fn new() -> Self {
+        let mut context = TestContext::new();
+        let owner = context.owner.clone();
+
+        context.call_with_bucket_and_proof(&owner, XRD, dec!(1000), "validator_stake_as_owner").expect_commit_success();
+        let stake_unit = context.stake_unit();
+        context.call_with_bucket_and_proof(&owner, stake_unit, dec!(500), "validator_lock_owner_stake_units").expect_commit_success();
+        context.call_with_proof(&owner, "validator_start_unlock_owner_stake_units", |proof| manifest_args!(dec!(100), proof)).expect_commit_success();
+        let matured = Epoch::of(context.ledger.get_current_epoch().number() + 100_000);
+        context.ledger.set_current_epoch(matured);
+
+        context.call_with_bucket_and_proof(&owner, XRD, dec!(1000), "deposit_to_treasury").expect_commit_success();
+        context.call_with_proof(&owner, "validator_stake_from_treasury", |proof| manifest_args!(dec!(100), proof)).expect_commit_success();
+        context.call_with_auth_zone(&owner, "set_payout_account", manifest_args!(Some(owner.account))).expect_commit_success();
+
+        let recall_target = context.new_owner_custom_key(&["CreateNativeProof"]);
+        let recall_target = context.key_badge_vault(&recall_target);
+        let recall_by_id_target = context.new_owner_custom_key(&["CreateNativeProof"]);
+        let recall_by_id_target = (context.key_badge_vault(&recall_by_id_target), recall_by_id_target.badge_id);
+        let recall_and_burn_target = context.new_owner_custom_key(&["CreateNativeProof"]);
+        let recall_and_burn_target = (context.key_badge_vault(&recall_and_burn_target), recall_and_burn_target.badge_id);
+
+        Self { context, stake_unit, recall_target, recall_by_id_target, recall_and_burn_target }
+    }
+
+    // a key holding only `permission`, its account also receives some of the owner's stake units
+    fn single_permission_key(&mut self, permission: &KeyBadgePermission) -> Caller {
+        let key = self.context.new_owner_custom_key(&[permission.to_string().as_str()]);
+        let owner = self.context.owner.clone();
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .withdraw_from_account(owner.account, self.stake_unit, dec!(10))
+            .try_deposit_entire_worktop_or_abort(key.account, None)
+            .build();
+        self.context.execute(manifest, &owner).expect_commit_success();
+        key
+    }
+}
+
+fn with_proof(name: &'static str, granted_by: Vec<KeyBadgePermission>, args: fn(&MatrixContext, Option<ManifestProof>) -> ManifestArgs) -> Operation {
+    Operation {
+        name,
+        granted_by,
+        when_granted: WhenGranted::Succeeds,
+        manifest: Box::new(move |matrix: &MatrixContext, caller: &Caller| matrix.context.proof_manifest(caller, name, |proof| args(matrix, proof))),
+    }
+}
+
+fn with_bucket(name: &'static str, granted_by: Vec<KeyBadgePermission>, resource: fn(&MatrixContext) -> ResourceAddress) -> Operation {
+    Operation {
+        name,
+        granted_by,
+        when_granted: WhenGranted::Succeeds,
+        manifest: Box::new(move |matrix: &MatrixContext, caller: &Caller| matrix.context.bucket_and_proof_manifest(caller, resource(matrix), dec!(1), name)),
+    }
+}
+
+fn set_metadata(key: &'static str, value: &'static str, granted_by: Vec<KeyBadgePermission>) -> Operation {
+    Operation {
+        name: "validator_set_metadata",
+        granted_by,
+        when_granted: WhenGranted::Succeeds,
+        manifest: Box::new(move |matrix: &MatrixContext, caller: &Caller| matrix.context.proof_manifest(caller, "validator_set_metadata", |proof| manifest_args!(key, value, proof))),
+    }
+}
+
+// The permission-to-method mapping under test, `operations_match_the_permission_gated_methods` checks it against the component's table
+fn operations() -> Vec<Operation> {
+    use KeyBadgePermission::*;
+    vec![
+        with_proof("create_custom_access_key_badge", vec![CreateAccessKey], |_, proof| manifest_args!(Vec::<String>::new(), proof)),
+        with_proof("create_custom_access_key_badge_with_policy", vec![CreateAccessKey], |_, proof| manifest_args!(Vec::<String>::new(), KeyTransferPolicy::Transferable, proof)),
+        with_proof("create_bound_access_key_badge", vec![CreateAccessKey], |matrix, proof| manifest_args!(Vec::<String>::new(), KeyTransferPolicy::Soulbound, matrix.context.owner.account, proof)),
+        Operation {
+            // basic keys always carry CreateNativeProof, which a single-permission key cannot grant
+            when_granted: WhenGranted::Panics(AccessManagerError::MissingGrantedPermission),
+            ..with_proof("create_basic_key_badge", vec![CreateAccessKey], |_, proof| manifest_args!(false, proof))
+        },
+        with_proof("recall_key_badge", vec![RecallAccessKey], |matrix, proof| manifest_args!(matrix.recall_target, proof)),
+        with_proof("recall_key_badges_by_id", vec![RecallAccessKey], |matrix, proof| manifest_args!(matrix.recall_by_id_target.0, vec![matrix.recall_by_id_target.1.clone()], proof)),
+        with_proof("recall_and_burn_key_badges", vec![RecallAccessKey], |matrix, proof| manifest_args!(vec![(matrix.recall_and_burn_target.0, vec![matrix.recall_and_burn_target.1.clone()])], proof)),
+        with_proof("create_auth_badge_proof", vec![CreateNativeProof], |_, proof| manifest_args!(proof)),
+        with_bucket("deposit_to_treasury", vec![Treasury_Deposit], |_| XRD),
+        with_proof("withdraw_from_treasury", vec![Treasury_Withdraw], |_, proof| manifest_args!(XRD, dec!(1), proof)),
+        with_proof("validator_register", vec![Validator_Register], |_, proof| manifest_args!(proof)),
+        with_proof("validator_unregister", vec![Validator_Unregister], |_, proof| manifest_args!(proof)),
+        with_bucket("validator_stake_as_owner", vec![Validator_StakeAsOwner], |_| XRD),
+        with_bucket("validator_stake_as_owner_to_payout", vec![Validator_StakeAsOwnerToPayout], |_| XRD),
+        with_proof("validator_stake_from_treasury", vec![Validator_StakeFromTreasury], |_, proof| manifest_args!(dec!(1), proof)),
+        with_proof("validator_update_key", vec![Validator_UpdateKey], |_, proof| manifest_args!(Secp256k1PrivateKey::from_u64(99).unwrap().public_key(), proof)),
+        with_proof("propose_validator_operation", vec![Validator_UpdateKey], |_, proof| manifest_args!(ValidatorOperation::UpdateKey(Secp256k1PrivateKey::from_u64(99).unwrap().public_key()), proof)),
+        with_proof("propose_validator_operation", vec![Validator_Unregister], |_, proof| manifest_args!(ValidatorOperation::Unregister, proof)),
+        with_proof("validator_update_fee", vec![Validator_UpdateFee], |_, proof| manifest_args!(dec!("0.01"), proof)),
+        with_proof("validator_update_accept_delegated_stake", vec![Validator_UpdateAcceptDelegatedStake], |_, proof| manifest_args!(false, proof)),
+        with_proof("validator_signal_protocol_update_readiness", vec![Validator_SignalProtocolUpdateReadiness], |_, proof| manifest_args!("a".repeat(32), proof)),
+        with_bucket("validator_lock_owner_stake_units", vec![Validator_LockOwnerStakeUnits], |matrix| matrix.stake_unit),
+        with_proof("validator_lock_owner_stake_units_from_treasury", vec![Validator_LockOwnerStakeUnitsFromTreasury], |_, proof| manifest_args!(dec!(1), proof)),
+        with_proof("validator_start_unlock_owner_stake_units", vec![Validator_StartUnlockOwnerStakeUnits], |_, proof| manifest_args!(dec!(1), proof)),
+        with_proof("validator_finish_unlock_owner_stake_units", vec![Validator_FinishUnlockOwnerStakeUnits], |_, proof| manifest_args!(proof)),
+        with_proof("validator_finish_unlock_owner_stake_units_to_payout", vec![Validator_FinishUnlockOwnerStakeUnitsToPayout], |_, proof| manifest_args!(proof)),
+        with_proof("validator_compound_owner_stake", vec![Validator_CompoundOwnerStake], |_, proof| manifest_args!(proof)),
+        set_metadata("name", "Matrix Validator", vec![Validator_SetMetadata, Validator_SetMetadata_Name]),
+        set_metadata("description", "Validator description", vec![Validator_SetMetadata, Validator_SetMetadata_Description]),
+        set_metadata("info_url", "https://example.com", vec![Validator_SetMetadata, Validator_SetMetadata_InfoUrl]),
+        set_metadata("icon_url", "https://example.com/icon.png", vec![Validator_SetMetadata, Validator_SetMetadata_IconUrl]),
+        set_metadata("operator_note", "any other key", vec![Validator_SetMetadata]),
+        with_proof("validator_lock_metadata", vec![Validator_LockMetadata], |_, proof| manifest_args!("operator_note", proof)),
+        with_proof("validator_set_metadata_role", vec![Validator_SetMetadataRole], |_, proof| manifest_args!("metadata_locker", rule!(require(VALIDATOR_OWNER_BADGE)), proof)),
+    ]
+}
+
+#[test]
+fn every_permission_is_mapped_to_an_operation() {
+    let operations = operations();
+    for permission in KeyBadgePermission::iter() {
+        assert!(
+            operations.iter().any(|operation| operation.granted_by.contains(&permission)),
+            "{} is not mapped to any operation, add it to `operations()`", permission
+        );
+    }
+}
+
+// `operations()` exercises exactly the permission table that `can_call` and `describe_key_permissions` are built from
+#[test]
+fn operations_match_the_permission_gated_methods() {
+    let operations = operations();
+    let gated_methods = permission_gated_methods();
+    for (method, granted_by) in &gated_methods {
+        let exercised: IndexSet<KeyBadgePermission> = operations
+            .iter()
+            .filter(|operation| operation.name == *method)
+            .flat_map(|operation| operation.granted_by.clone())
+            .collect();
+        assert_eq!(exercised, granted_by.iter().cloned().collect::<IndexSet<_>>(), "`operations()` does not exercise the permissions of {}", method);
+    }
+    for operation in &operations {
+        assert!(gated_methods.iter().any(|(method, _)| *method == operation.name), "{} is not in `permission_gated_methods`", operation.name);
+    }
+}
+
+#[test]
+fn single_permission_keys_only_pass_their_mapped_operations() {
+    let operations = operations();
+    for permission in KeyBadgePermission::iter() {
+        let mut matrix = MatrixContext::new();
+        let key = matrix.single_permission_key(&permission);
+
+        for operation in &operations {
+            // `can_call` has no arguments to tell the scoped metadata keys apart, it allows the method if any mapping grants it
+            let mapped = operations.iter().any(|other| other.name == operation.name && other.granted_by.contains(&permission));
+            assert_eq!(matrix.context.can_call(&key, operation.name), mapped, "can_call({}) for a {} key", operation.name, permission);
+
+            let manifest = (operation.manifest)(&matrix, &key);
+            let receipt = matrix.context.execute(manifest, &key);
+            let message = panic_message(&receipt);
+
+            if !operation.granted_by.contains(&permission) {
+                assert!(
+                    panic_error(&receipt) == Some(AccessManagerError::NotAuthorized),
+                    "{} key should be rejected by {}, got {:?}", permission, operation.name, message
+                );
+                continue;
+            }
+            match operation.when_granted {
+                WhenGranted::Succeeds => assert!(
+                    receipt.is_commit_success(),
+                    "{} key should be allowed to call {}, got {:?}", permission, operation.name, message
+                ),
+                WhenGranted::Panics(expected) => assert!(
+                    panic_error(&receipt) == Some(expected),
+                    "{} key calling {} should fail with {:?}, got {:?}", permission, operation.name, expected, message
+                ),
+            }
+        }
+    }
+}